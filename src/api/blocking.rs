@@ -0,0 +1,284 @@
+//! A blocking client for the Telegram Bot API.
+
+use std::{
+    fmt,
+    future::Future,
+    io::{Result as IoResult, Write},
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures_util::stream::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use tokio::{
+    io::AsyncWrite,
+    runtime::{Builder as RuntimeBuilder, Handle, Runtime},
+};
+
+use super::{
+    ClientError,
+    DownloadFileError,
+    DownloadOptions,
+    DownloadSource,
+    ExecuteError,
+    ExecuteOptions,
+    Method,
+    client::Client as AsyncClient,
+    token::BotToken,
+};
+use crate::types::File;
+
+/// A blocking client for interacting with the Telegram Bot API.
+///
+/// Wraps [`crate::api::Client`] and runs it on a private single-threaded runtime,
+/// so the settings, retries and interceptors of the async client are applied as is.
+///
+/// Methods of the client must not be called within an async runtime;
+/// use [`crate::api::Client`] there instead.
+/// The client can be created and dropped anywhere.
+///
+/// # Example
+///
+/// ```no_run
+/// use tgbot::{api::blocking::Client, types::SendMessage};
+///
+/// let client = Client::new("token").unwrap();
+/// client.execute(SendMessage::new(1, "text")).unwrap();
+/// ```
+#[cfg_attr(nightly, doc(cfg(feature = "blocking")))]
+#[derive(Clone)]
+pub struct Client {
+    inner: AsyncClient,
+    runtime: Arc<BlockingRuntime>,
+}
+
+impl Client {
+    /// Creates a new blocking client with the provided bot token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - A token associated with your bot.
+    pub fn new<T>(token: T) -> Result<Self, ClientError>
+    where
+        T: Into<BotToken>,
+    {
+        Self::from_async(AsyncClient::new(token)?)
+    }
+
+    /// Creates a new blocking client from an async client.
+    ///
+    /// Use it to configure the client using methods of [`crate::api::Client`].
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The async client.
+    pub fn from_async(client: AsyncClient) -> Result<Self, ClientError> {
+        Ok(Self {
+            inner: client,
+            runtime: Arc::new(BlockingRuntime::new()?),
+        })
+    }
+
+    /// Returns the underlying async client.
+    pub fn as_async(&self) -> &AsyncClient {
+        &self.inner
+    }
+
+    /// Downloads a file.
+    ///
+    /// Returns an iterator over chunks of the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The path to the file to be downloaded.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called within an async runtime, as well as [`Iterator::next`] of the result.
+    pub fn download_file<P>(&self, file_path: P) -> Result<DownloadFileIter, DownloadFileError>
+    where
+        P: AsRef<str>,
+    {
+        let stream = self
+            .runtime
+            .block_on(self.inner.download_file(file_path.as_ref().to_string()))?;
+        Ok(DownloadFileIter {
+            stream: Box::pin(stream),
+            runtime: self.runtime.clone(),
+        })
+    }
+
+    /// Downloads a file to a filesystem.
+    ///
+    /// See [`crate::api::Client::download_to_path`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - A `file_id` or a [`File`] to download.
+    /// * `path` - The path to save the file to.
+    /// * `options` - The options of the download.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called within an async runtime.
+    pub fn download_to_path<S, P>(
+        &self,
+        source: S,
+        path: P,
+        options: DownloadOptions,
+    ) -> Result<File, DownloadFileError>
+    where
+        S: Into<DownloadSource>,
+        P: AsRef<Path>,
+    {
+        self.runtime
+            .block_on(self.inner.download_to_path(source, path, options))
+    }
+
+    /// Downloads a file to a writer.
+    ///
+    /// See [`crate::api::Client::download_to_writer`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - A `file_id` or a [`File`] to download.
+    /// * `writer` - The writer to write the file to.
+    /// * `options` - The options of the download.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called within an async runtime.
+    pub fn download_to_writer<S, W>(
+        &self,
+        source: S,
+        writer: &mut W,
+        options: DownloadOptions,
+    ) -> Result<File, DownloadFileError>
+    where
+        S: Into<DownloadSource>,
+        W: Write,
+    {
+        let mut writer = SyncWriter(writer);
+        self.runtime
+            .block_on(self.inner.download_to_writer(source, &mut writer, options))
+    }
+
+    /// Executes a method.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The method to execute.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called within an async runtime.
+    pub fn execute<M>(&self, method: M) -> Result<M::Response, ExecuteError>
+    where
+        M: Method,
+        M::Response: DeserializeOwned + Send + 'static,
+    {
+        self.runtime.block_on(self.inner.execute(method))
+    }
+
+    /// Executes a method with options.
+    ///
+    /// See [`crate::api::Client::execute_with`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The method to execute.
+    /// * `options` - The options of the execution.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called within an async runtime.
+    pub fn execute_with<M>(&self, method: M, options: ExecuteOptions) -> Result<M::Response, ExecuteError>
+    where
+        M: Method,
+        M::Response: DeserializeOwned + Send + 'static,
+    {
+        self.runtime.block_on(self.inner.execute_with(method, options))
+    }
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_struct("Client").field("inner", &self.inner).finish()
+    }
+}
+
+/// An iterator over chunks of a file downloaded by [`Client::download_file`].
+#[cfg_attr(nightly, doc(cfg(feature = "blocking")))]
+pub struct DownloadFileIter {
+    stream: Pin<Box<dyn Stream<Item = Result<Bytes, DownloadFileError>> + Send>>,
+    runtime: Arc<BlockingRuntime>,
+}
+
+impl Iterator for DownloadFileIter {
+    type Item = Result<Bytes, DownloadFileError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
+
+impl fmt::Debug for DownloadFileIter {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_struct("DownloadFileIter").finish()
+    }
+}
+
+/// A runtime which can be dropped within an async runtime.
+struct BlockingRuntime(Option<Runtime>);
+
+impl BlockingRuntime {
+    fn new() -> Result<Self, ClientError> {
+        RuntimeBuilder::new_current_thread()
+            .enable_all()
+            .build()
+            .map(|runtime| Self(Some(runtime)))
+            .map_err(ClientError::BuildRuntime)
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        assert!(
+            Handle::try_current().is_err(),
+            "The blocking client can not be used within an async runtime, use tgbot::api::Client instead"
+        );
+        self.0.as_ref().expect("The runtime is dropped").block_on(future)
+    }
+}
+
+impl Drop for BlockingRuntime {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.0.take()
+            && Handle::try_current().is_ok()
+        {
+            // Dropping a runtime within another one panics.
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// Writes chunks of a download to a blocking writer.
+struct SyncWriter<'a, W>(&'a mut W);
+
+impl<W> AsyncWrite for SyncWriter<'_, W>
+where
+    W: Write,
+{
+    fn poll_write(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>> {
+        Poll::Ready(self.get_mut().0.write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Poll::Ready(self.get_mut().0.flush())
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        self.poll_flush(cx)
+    }
+}
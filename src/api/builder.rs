@@ -0,0 +1,334 @@
+use std::{
+    env::{self, VarError},
+    ffi::OsString,
+    fmt,
+    sync::Arc,
+    time::Duration,
+};
+
+use reqwest::{ClientBuilder as HttpClientBuilder, Proxy};
+
+use super::{
+    client::{Client, ClientError},
+    retry::RetryPolicy,
+    token::BotToken,
+};
+
+const TOKEN_ENV: &str = "TGBOT_TOKEN";
+const PROXY_ENV: &str = "TGBOT_PROXY";
+const HOST_ENV: &str = "TGBOT_HOST";
+
+/// A builder for [`Client`].
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use tgbot::api::{ClientBuilder, ExponentialBackoff};
+///
+/// let client = ClientBuilder::new("token")
+///     .with_proxy(reqwest::Proxy::all("socks5://127.0.0.1:1080").unwrap())
+///     .with_connect_timeout(Duration::from_secs(5))
+///     .with_user_agent("my-bot/1.0")
+///     .with_retry_policy(ExponentialBackoff::default())
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct ClientBuilder {
+    token: BotToken,
+    host: Option<String>,
+    proxy: Option<Proxy>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    root_certificates: Vec<Vec<u8>>,
+    max_retries: Option<u8>,
+    max_retry_after: Option<u64>,
+    retry_policy: Option<Arc<dyn RetryPolicy>>,
+}
+
+impl ClientBuilder {
+    /// Creates a new `ClientBuilder`.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - A token associated with your bot.
+    pub fn new<T>(token: T) -> Self
+    where
+        T: Into<BotToken>,
+    {
+        Self {
+            token: token.into(),
+            host: None,
+            proxy: None,
+            connect_timeout: None,
+            read_timeout: None,
+            user_agent: None,
+            root_certificates: Vec::new(),
+            max_retries: None,
+            max_retry_after: None,
+            retry_policy: None,
+        }
+    }
+
+    /// Creates a new `ClientBuilder` using environment variables.
+    ///
+    /// * `TGBOT_TOKEN` - A token associated with your bot; required.
+    /// * `TGBOT_PROXY` - A URL of an HTTP or SOCKS proxy; optional.
+    /// * `TGBOT_HOST` - An API host; optional.
+    pub fn from_env() -> Result<Self, ClientError> {
+        Self::from_vars(env::var_os)
+    }
+
+    fn from_vars<F>(get_var: F) -> Result<Self, ClientError>
+    where
+        F: Fn(&'static str) -> Option<OsString>,
+    {
+        let get_var = |name| {
+            get_var(name)
+                .map(|value| {
+                    value.into_string().map_err(|value| ClientError::EnvVar {
+                        name,
+                        error: VarError::NotUnicode(value),
+                    })
+                })
+                .transpose()
+        };
+        let token = get_var(TOKEN_ENV)?.ok_or(ClientError::EnvVar {
+            name: TOKEN_ENV,
+            error: VarError::NotPresent,
+        })?;
+        let mut builder = Self::new(token);
+        if let Some(proxy) = get_var(PROXY_ENV)? {
+            builder = builder.with_proxy(Proxy::all(proxy).map_err(ClientError::BuildClient)?);
+        }
+        if let Some(host) = get_var(HOST_ENV)? {
+            builder = builder.with_host(host);
+        }
+        Ok(builder)
+    }
+
+    /// Overrides the default API host with a custom one.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The new API host to use.
+    pub fn with_host<T>(mut self, value: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.host = Some(value.into());
+        self
+    }
+
+    /// Sets a proxy.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - An HTTP or SOCKS proxy.
+    pub fn with_proxy(mut self, value: Proxy) -> Self {
+        self.proxy = Some(value);
+        self
+    }
+
+    /// Sets a timeout for connecting to the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The timeout; default - none.
+    pub fn with_connect_timeout(mut self, value: Duration) -> Self {
+        self.connect_timeout = Some(value);
+        self
+    }
+
+    /// Sets a timeout for each read operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The timeout; default - none;
+    ///   should be greater than the timeout of long polling.
+    pub fn with_read_timeout(mut self, value: Duration) -> Self {
+        self.read_timeout = Some(value);
+        self
+    }
+
+    /// Sets a value of the `User-Agent` header.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value; default - none.
+    pub fn with_user_agent<T>(mut self, value: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.user_agent = Some(value.into());
+        self
+    }
+
+    /// Adds a trusted root certificate.
+    ///
+    /// Built-in root certificates are trusted as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A DER-encoded certificate.
+    pub fn with_root_certificate<T>(mut self, value: T) -> Self
+    where
+        T: Into<Vec<u8>>,
+    {
+        self.root_certificates.push(value.into());
+        self
+    }
+
+    /// Overrides the default number of max retries.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The new number of max retries; see [`Client::with_max_retries`].
+    pub fn with_max_retries(mut self, value: u8) -> Self {
+        self.max_retries = Some(value);
+        self
+    }
+
+    /// Sets the maximum possible value for the retry after duration.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Duration in seconds; see [`Client::with_max_retry_after`].
+    pub fn with_max_retry_after(mut self, value: u64) -> Self {
+        self.max_retry_after = Some(value);
+        self
+    }
+
+    /// Sets a policy for retrying requests failed due to transport or server errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The retry policy; see [`Client::with_retry_policy`].
+    pub fn with_retry_policy<T>(mut self, value: T) -> Self
+    where
+        T: RetryPolicy + 'static,
+    {
+        self.retry_policy = Some(Arc::new(value));
+        self
+    }
+
+    /// Builds a new [`Client`].
+    pub fn build(self) -> Result<Client, ClientError> {
+        let mut http_client = {
+            #[cfg(feature = "webpki-roots")]
+            {
+                let mut root_cert_store =
+                    rustls::RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+                for certificate in self.root_certificates {
+                    root_cert_store
+                        .add(rustls::pki_types::CertificateDer::from(certificate))
+                        .map_err(|err| ClientError::InvalidCertificate(Box::new(err)))?;
+                }
+
+                let tls_config = rustls::ClientConfig::builder()
+                    .with_root_certificates(root_cert_store)
+                    .with_no_client_auth();
+
+                HttpClientBuilder::new().tls_backend_preconfigured(tls_config)
+            }
+
+            #[cfg(not(feature = "webpki-roots"))]
+            {
+                let mut root_certificates = Vec::with_capacity(self.root_certificates.len());
+                for certificate in self.root_certificates {
+                    root_certificates.push(
+                        reqwest::Certificate::from_der(&certificate)
+                            .map_err(|err| ClientError::InvalidCertificate(Box::new(err)))?,
+                    );
+                }
+                HttpClientBuilder::new()
+                    .tls_backend_rustls()
+                    .tls_certs_merge(root_certificates)
+            }
+        };
+        if let Some(proxy) = self.proxy {
+            http_client = http_client.proxy(proxy);
+        }
+        if let Some(connect_timeout) = self.connect_timeout {
+            http_client = http_client.connect_timeout(connect_timeout);
+        }
+        if let Some(read_timeout) = self.read_timeout {
+            http_client = http_client.read_timeout(read_timeout);
+        }
+        if let Some(user_agent) = self.user_agent {
+            http_client = http_client.user_agent(user_agent);
+        }
+        let http_client = http_client.build().map_err(ClientError::BuildClient)?;
+
+        let mut client = Client::with_http_client(http_client, self.token);
+        if let Some(host) = self.host {
+            client = client.with_host(host);
+        }
+        if let Some(max_retries) = self.max_retries {
+            client = client.with_max_retries(max_retries);
+        }
+        if let Some(max_retry_after) = self.max_retry_after {
+            client = client.with_max_retry_after(max_retry_after);
+        }
+        if let Some(retry_policy) = self.retry_policy {
+            client = client.with_retry_policy(retry_policy);
+        }
+        Ok(client)
+    }
+}
+
+impl fmt::Debug for ClientBuilder {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_struct("ClientBuilder")
+            .field("token", &format_args!("..."))
+            .field("host", &self.host)
+            .field("proxy", &self.proxy)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field("user_agent", &self.user_agent)
+            .field("root_certificates", &self.root_certificates.len())
+            .field("max_retries", &self.max_retries)
+            .field("max_retry_after", &self.max_retry_after)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_vars() {
+        let builder = ClientBuilder::from_vars(|name| match name {
+            TOKEN_ENV => Some(OsString::from("token")),
+            PROXY_ENV => Some(OsString::from("socks5://127.0.0.1:1080")),
+            HOST_ENV => Some(OsString::from("https://example.com")),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(builder.token.expose_secret(), "token");
+        assert!(builder.proxy.is_some());
+        assert_eq!(builder.host.as_deref(), Some("https://example.com"));
+
+        let builder = ClientBuilder::from_vars(|name| (name == TOKEN_ENV).then(|| OsString::from("token"))).unwrap();
+        assert!(builder.proxy.is_none());
+        assert!(builder.host.is_none());
+
+        let err = ClientBuilder::from_vars(|_| None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "can not read environment variable TGBOT_TOKEN: environment variable not found"
+        );
+
+        let err = ClientBuilder::from_vars(|name| match name {
+            TOKEN_ENV => Some(OsString::from("token")),
+            PROXY_ENV => Some(OsString::from("invalid url")),
+            _ => None,
+        })
+        .unwrap_err();
+        assert!(matches!(err, ClientError::BuildClient(_)));
+    }
+}
@@ -0,0 +1,318 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{Cursor, Error as IoError},
+    sync::Mutex,
+};
+
+use bytes::BytesMut;
+use futures_util::{future::BoxFuture, stream::TryStreamExt};
+use log::debug;
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use tokio::fs::File;
+use tokio_util::{
+    codec::{BytesCodec, FramedRead},
+    io::ReaderStream,
+};
+
+use super::{
+    client::ExecuteError,
+    form::FormValue,
+    interceptor::{Interceptor, Next},
+    payload::{Payload, PayloadData},
+    token::BotToken,
+};
+use crate::types::{Response, ResponseErrorKind};
+
+const CACHED_FIELDS: [(&str, &str); 8] = [
+    ("sendAnimation", "animation"),
+    ("sendAudio", "audio"),
+    ("sendDocument", "document"),
+    ("sendPhoto", "photo"),
+    ("sendSticker", "sticker"),
+    ("sendVideo", "video"),
+    ("sendVideoNote", "video_note"),
+    ("sendVoice", "voice"),
+];
+
+/// Represents a storage of file IDs used by [`FileIdCache`].
+///
+/// A key consists of an identifier of the bot, a name of the file field
+/// and a hex-encoded SHA-256 hash of the file content, e.g. `123:document:<hash>`,
+/// since file IDs can not be used by other bots.
+/// When a token has no valid bot identifier, a hash of the token is used instead.
+pub trait FileIdStorage: Send + Sync {
+    /// Returns a file ID by a key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the file.
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<String>>;
+
+    /// Stores a file ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the file.
+    /// * `file_id` - The file ID.
+    fn set(&self, key: String, file_id: String) -> BoxFuture<'_, ()>;
+
+    /// Removes a file ID rejected by the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the file.
+    fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, ()>;
+}
+
+/// Stores file IDs in memory.
+#[derive(Debug, Default)]
+pub struct InMemoryFileIdStorage {
+    items: Mutex<HashMap<String, String>>,
+}
+
+impl FileIdStorage for InMemoryFileIdStorage {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<String>> {
+        let file_id = self.items.lock().unwrap().get(key).cloned();
+        Box::pin(async move { file_id })
+    }
+
+    fn set(&self, key: String, file_id: String) -> BoxFuture<'_, ()> {
+        self.items.lock().unwrap().insert(key, file_id);
+        Box::pin(async {})
+    }
+
+    fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, ()> {
+        self.items.lock().unwrap().remove(key);
+        Box::pin(async {})
+    }
+}
+
+/// An interceptor which caches file IDs of uploaded files.
+///
+/// A file uploaded using `sendAnimation`, `sendAudio`, `sendDocument`, `sendPhoto`,
+/// `sendSticker`, `sendVideo`, `sendVideoNote` or `sendVoice` is hashed before sending.
+/// After the first successful send, the `file_id` is taken from the returned message;
+/// later uploads of the same content by the same bot are replaced with the `file_id`.
+/// When the server rejects a cached `file_id`, it is removed from the storage
+/// and the file is uploaded again.
+///
+/// Files created using [`crate::types::InputFile::path`] are hashed while reading.
+/// Files created from readers are loaded into memory in order to be hashed,
+/// and the whole content is kept in memory until the request is completed;
+/// use paths for large files.
+///
+/// A cached `file_id` is not used when the method contains other files created from readers,
+/// since such a method can not be sent again.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::api::{Client, FileIdCache};
+///
+/// let client = Client::new("token").unwrap().with_interceptor(FileIdCache::default());
+/// ```
+pub struct FileIdCache<S = InMemoryFileIdStorage> {
+    storage: S,
+}
+
+impl<S> FileIdCache<S>
+where
+    S: FileIdStorage,
+{
+    /// Creates a new `FileIdCache`.
+    ///
+    /// # Arguments
+    ///
+    /// * `storage` - The storage of file IDs.
+    pub fn new(storage: S) -> Self {
+        Self { storage }
+    }
+
+    /// Returns the storage of file IDs.
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    async fn send(&self, mut payload: Payload, next: Next<'_>) -> Result<Response<JsonValue>, ExecuteError> {
+        let method_name = payload.method_name();
+        let Some(field_name) = CACHED_FIELDS
+            .iter()
+            .find_map(|(name, field_name)| (*name == method_name).then_some(*field_name))
+        else {
+            return next.run(payload).await;
+        };
+        let PayloadData::Form(form) = &mut payload.payload_data else {
+            return next.run(payload).await;
+        };
+        let Some(mut value) = form.take_field(field_name) else {
+            return next.run(payload).await;
+        };
+        let key = match hash_form_value(&mut value).await {
+            Ok(Some(hash)) => format!("{}:{field_name}:{hash}", get_bot_key(&next.token())),
+            Ok(None) => {
+                payload.insert_form_value(field_name, value);
+                return next.run(payload).await;
+            }
+            Err(err) => {
+                debug!("Could not hash a file: {err}");
+                payload.insert_form_value(field_name, value);
+                return next.run(payload).await;
+            }
+        };
+        if let Some(file_id) = self.storage.get(&key).await {
+            payload.insert_form_value(field_name, FormValue::Text(file_id));
+            match payload.try_clone() {
+                Some(mut upload_payload) => {
+                    debug!("Using cached file ID for {field_name}");
+                    let response = next.clone().run(payload).await?;
+                    match &response {
+                        Response::Error(err) if err.kind() == ResponseErrorKind::InvalidFileId => {
+                            debug!("Cached file ID for {field_name} has been rejected, uploading the file");
+                            self.storage.remove(&key).await;
+                            upload_payload.insert_form_value(field_name, value);
+                            return self.upload(key, field_name, upload_payload, next).await;
+                        }
+                        _ => return Ok(response),
+                    }
+                }
+                None => debug!("Could not clone payload, uploading the file"),
+            }
+        }
+        payload.insert_form_value(field_name, value);
+        self.upload(key, field_name, payload, next).await
+    }
+
+    async fn upload(
+        &self,
+        key: String,
+        field_name: &str,
+        payload: Payload,
+        next: Next<'_>,
+    ) -> Result<Response<JsonValue>, ExecuteError> {
+        let response = next.run(payload).await?;
+        if let Response::Success(message) = &response
+            && let Some(file_id) = get_file_id(message, field_name)
+        {
+            self.storage.set(key, file_id).await;
+        }
+        Ok(response)
+    }
+}
+
+impl Default for FileIdCache {
+    fn default() -> Self {
+        Self::new(InMemoryFileIdStorage::default())
+    }
+}
+
+impl<S> fmt::Debug for FileIdCache<S> {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_struct("FileIdCache").finish()
+    }
+}
+
+impl<S> Interceptor for FileIdCache<S>
+where
+    S: FileIdStorage,
+{
+    fn intercept<'a>(
+        &'a self,
+        payload: Payload,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response<JsonValue>, ExecuteError>> {
+        Box::pin(self.send(payload, next))
+    }
+}
+
+/// Returns a hash of the file content.
+///
+/// A file created from a reader is replaced with the loaded content.
+async fn hash_form_value(value: &mut FormValue) -> Result<Option<String>, IoError> {
+    let mut hasher = Sha256::new();
+    match value {
+        FormValue::Text(_) => return Ok(None),
+        FormValue::File { reader, .. } => {
+            let data = reader
+                .try_fold(BytesMut::new(), |mut data, chunk| async move {
+                    data.extend_from_slice(&chunk);
+                    Ok(data)
+                })
+                .await?
+                .freeze();
+            hasher.update(&data);
+            *reader = FramedRead::new(Box::new(Cursor::new(data)), BytesCodec::new());
+        }
+        FormValue::Path { path, .. } => {
+            let mut reader = ReaderStream::new(File::open(path).await?);
+            while let Some(chunk) = reader.try_next().await? {
+                hasher.update(&chunk);
+            }
+        }
+    }
+    Ok(Some(to_hex(&hasher.finalize())))
+}
+
+/// Returns a part of the key identifying the bot.
+fn get_bot_key(token: &BotToken) -> String {
+    match token.bot_id() {
+        Some(bot_id) => bot_id.to_string(),
+        None => to_hex(&Sha256::digest(token.expose_secret())[..8]),
+    }
+}
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|x| format!("{x:02x}")).collect()
+}
+
+/// Returns a file ID of the sent file from a message.
+fn get_file_id(message: &JsonValue, field_name: &str) -> Option<String> {
+    let value = message.get(field_name)?;
+    let value = match value {
+        JsonValue::Array(sizes) => sizes.last()?,
+        value => value,
+    };
+    value.get("file_id")?.as_str().map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn file_id() {
+        let message = json!({
+            "photo": [
+                {"file_id": "small", "file_unique_id": "small"},
+                {"file_id": "large", "file_unique_id": "large"},
+            ],
+            "document": {"file_id": "document", "file_unique_id": "document"}
+        });
+        assert_eq!(get_file_id(&message, "photo").unwrap(), "large");
+        assert_eq!(get_file_id(&message, "document").unwrap(), "document");
+        assert!(get_file_id(&message, "video").is_none());
+    }
+
+    #[test]
+    fn bot_key() {
+        assert_eq!(get_bot_key(&BotToken::from("123:token")), "123");
+        assert_eq!(get_bot_key(&BotToken::from("token")).len(), 16);
+        assert_ne!(
+            get_bot_key(&BotToken::from("token")),
+            get_bot_key(&BotToken::from("other-token"))
+        );
+    }
+
+    #[tokio::test]
+    async fn in_memory_storage() {
+        let storage = InMemoryFileIdStorage::default();
+        assert!(storage.get("key").await.is_none());
+        storage.set(String::from("key"), String::from("file-id")).await;
+        assert_eq!(storage.get("key").await.unwrap(), "file-id");
+        storage.remove("key").await;
+        assert!(storage.get("key").await.is_none());
+    }
+}
@@ -0,0 +1,298 @@
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt,
+    io::Error as IoError,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::{Error as JsonError, Map as JsonMap, Value as JsonValue, json, value::RawValue as RawJsonValue};
+use tokio::fs;
+
+use super::{
+    client::ExecuteError,
+    form::FormValue,
+    interceptor::{Interceptor, Next},
+    payload::{Payload, PayloadData},
+    token::BotToken,
+};
+use crate::types::Response;
+
+const TOKEN_METHODS: [&str; 2] = ["getManagedBotToken", "replaceManagedBotToken"];
+
+/// An interceptor which records requests and responses to a cassette file.
+///
+/// The file is written once the recorder is dropped, i.e. when the last clone of the client is dropped;
+/// it contains a JSON object with the `interactions` array.
+/// Each interaction contains a method name, fields, metadata of files
+/// and a response as received from the server.
+/// Tokens returned by `getManagedBotToken` and `replaceManagedBotToken` are masked.
+///
+/// The recorder must be the last interceptor in order to record requests and responses as they are sent and received.
+/// Transport errors are not recorded.
+///
+/// Use [`CassettePlayer`] to replay the recorded responses.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::api::{CassetteRecorder, Client};
+///
+/// let client = Client::new("token")
+///     .unwrap()
+///     .with_interceptor(CassetteRecorder::new("tests/cassettes/send_message.json"));
+/// ```
+pub struct CassetteRecorder {
+    path: PathBuf,
+    interactions: Mutex<Vec<Interaction>>,
+}
+
+impl CassetteRecorder {
+    /// Creates a new `CassetteRecorder`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the cassette file; the file is overwritten.
+    pub fn new<T>(path: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self {
+            path: path.into(),
+            interactions: Default::default(),
+        }
+    }
+
+    async fn record(&self, payload: Payload, next: Next<'_>) -> Result<Response<JsonValue>, ExecuteError> {
+        let request = RecordedRequest::from(&payload);
+        let received = next.run_raw(payload).await?;
+        let response = match &received.response {
+            Response::Success(JsonValue::String(token)) if TOKEN_METHODS.contains(&request.method.as_str()) => {
+                let mut response: JsonValue = serde_json::from_str(received.body.get())?;
+                response["result"] = JsonValue::String(BotToken::from(token.as_str()).masked());
+                RawJsonValue::from_string(serde_json::to_string(&response)?)?
+            }
+            _ => received.body,
+        };
+        self.interactions
+            .lock()
+            .unwrap()
+            .push(Interaction { request, response });
+        Ok(received.response)
+    }
+}
+
+impl Drop for CassetteRecorder {
+    fn drop(&mut self) {
+        let cassette = Cassette {
+            interactions: std::mem::take(self.interactions.get_mut().unwrap()),
+        };
+        if let Err(err) = write_cassette(&self.path, &cassette) {
+            log::error!("Could not write a cassette to {}: {err}", self.path.display());
+        }
+    }
+}
+
+impl fmt::Debug for CassetteRecorder {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_struct("CassetteRecorder").field("path", &self.path).finish()
+    }
+}
+
+impl Interceptor for CassetteRecorder {
+    fn intercept<'a>(
+        &'a self,
+        payload: Payload,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response<JsonValue>, ExecuteError>> {
+        Box::pin(self.record(payload, next))
+    }
+}
+
+/// An interceptor which replays responses recorded by [`CassetteRecorder`].
+///
+/// Requests are not sent; responses are returned in the recorded order.
+///
+/// # Panics
+///
+/// Executing a method panics when the method or its fields differ from the recorded request,
+/// or when there are no responses left.
+///
+/// # Example
+///
+/// ```
+/// # async fn replay() {
+/// use tgbot::api::{CassettePlayer, Client};
+///
+/// let player = CassettePlayer::open("tests/cassettes/send_message.json").await.unwrap();
+/// let client = Client::new("token").unwrap().with_interceptor(player);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CassettePlayer {
+    interactions: Mutex<VecDeque<Interaction>>,
+}
+
+impl CassettePlayer {
+    /// Opens a cassette file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the cassette file.
+    pub async fn open<T>(path: T) -> Result<Self, CassetteError>
+    where
+        T: AsRef<Path>,
+    {
+        let data = fs::read(path).await?;
+        let cassette: Cassette = serde_json::from_slice(&data)?;
+        Ok(Self {
+            interactions: Mutex::new(cassette.interactions.into()),
+        })
+    }
+
+    /// Returns a number of responses left.
+    pub fn remaining(&self) -> usize {
+        self.interactions.lock().unwrap().len()
+    }
+
+    fn replay(&self, payload: &Payload) -> Result<Response<JsonValue>, ExecuteError> {
+        let request = RecordedRequest::from(payload);
+        let interaction = self.interactions.lock().unwrap().pop_front();
+        let Some(interaction) = interaction else {
+            panic!("No recorded response for {}", request.method);
+        };
+        assert_eq!(
+            request.method, interaction.request.method,
+            "Unexpected method {}, expected {}",
+            request.method, interaction.request.method
+        );
+        assert_eq!(
+            request.fields, interaction.request.fields,
+            "Unexpected fields of {}",
+            request.method
+        );
+        Ok(serde_json::from_str(interaction.response.get())?)
+    }
+}
+
+impl Interceptor for CassettePlayer {
+    fn intercept<'a>(
+        &'a self,
+        payload: Payload,
+        _next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response<JsonValue>, ExecuteError>> {
+        let result = self.replay(&payload);
+        Box::pin(async move { result })
+    }
+}
+
+/// Represents an error when opening a cassette.
+#[derive(Debug, derive_more::From)]
+pub enum CassetteError {
+    /// Failed to read the file
+    Io(IoError),
+    /// Failed to parse the file
+    Json(JsonError),
+}
+
+impl Error for CassetteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(match self {
+            CassetteError::Io(err) => err,
+            CassetteError::Json(err) => err,
+        })
+    }
+}
+
+impl fmt::Display for CassetteError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CassetteError::Io(err) => write!(out, "can not read a cassette: {err}"),
+            CassetteError::Json(err) => write!(out, "can not parse a cassette: {err}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Cassette {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: Box<RawJsonValue>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct RecordedRequest {
+    method: String,
+    #[serde(default, skip_serializing_if = "JsonMap::is_empty")]
+    fields: JsonMap<String, JsonValue>,
+    #[serde(default, skip_serializing_if = "JsonMap::is_empty")]
+    files: JsonMap<String, JsonValue>,
+}
+
+impl From<&Payload> for RecordedRequest {
+    fn from(payload: &Payload) -> Self {
+        let mut fields = JsonMap::new();
+        let mut files = JsonMap::new();
+        match &payload.payload_data {
+            PayloadData::Form(form) => {
+                for (name, value) in form.iter() {
+                    match value {
+                        FormValue::Text(value) => {
+                            fields.insert(name.clone(), JsonValue::String(value.clone()));
+                        }
+                        FormValue::File {
+                            name: file_name,
+                            mime_type,
+                            ..
+                        } => {
+                            files.insert(
+                                name.clone(),
+                                json!({
+                                    "file_name": file_name,
+                                    "mime_type": mime_type.as_ref().map(|x| x.to_string()),
+                                }),
+                            );
+                        }
+                        FormValue::Path {
+                            name: file_name,
+                            mime_type,
+                            path,
+                            ..
+                        } => {
+                            files.insert(
+                                name.clone(),
+                                json!({
+                                    "file_name": file_name,
+                                    "mime_type": mime_type.as_ref().map(|x| x.to_string()),
+                                    "path": path,
+                                }),
+                            );
+                        }
+                    }
+                }
+            }
+            PayloadData::Json(Ok(JsonValue::Object(data))) => fields = data.clone(),
+            PayloadData::Json(_) | PayloadData::Empty => {}
+        }
+        Self {
+            method: payload.method_name().to_string(),
+            fields,
+            files,
+        }
+    }
+}
+
+fn write_cassette(path: &Path, cassette: &Cassette) -> Result<(), CassetteError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(cassette)?)?;
+    Ok(())
+}
@@ -1,67 +1,84 @@
-use std::{error::Error, fmt, time::Duration};
+use std::{
+    env::VarError,
+    error::Error,
+    fmt,
+    future::Future,
+    io::{Error as IoError, SeekFrom},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 
 use bytes::Bytes;
-use futures_util::stream::Stream;
+use futures_util::{
+    future::{BoxFuture, Either},
+    stream::{self, Stream, StreamExt, TryStreamExt},
+};
 use log::debug;
 use reqwest::{
     Client as HttpClient,
-    ClientBuilder as HttpClientBuilder,
     Error as HttpError,
     RequestBuilder as HttpRequestBuilder,
+    StatusCode,
+    header::RANGE,
 };
 use serde::de::DeserializeOwned;
-use tokio::time::sleep;
+use serde_json::{Error as JsonError, Value as JsonValue, value::RawValue as RawJsonValue};
+use tokio::{
+    fs::{self, File, OpenOptions},
+    io::{AsyncSeekExt, AsyncWrite, AsyncWriteExt},
+    time::{Instant, sleep, timeout_at},
+};
+use tokio_util::io::ReaderStream;
 
-use super::payload::{Payload, PayloadError};
-use crate::types::{Response, ResponseError};
+use super::{
+    builder::ClientBuilder,
+    download::{DownloadOptions, DownloadSource},
+    interceptor::{Interceptor, Next},
+    limiter::{RateLimiter, get_rate_limited_chat_id},
+    observer::ClientObserver,
+    options::ExecuteOptions,
+    payload::{Payload, PayloadError},
+    retry::RetryPolicy,
+    token::BotToken,
+};
+use crate::types::{ChatId, ChatPeerId, File as TelegramFile, GetFile, Response, ResponseError, ResponseErrorKind};
 
 const DEFAULT_HOST: &str = "https://api.telegram.org";
 const DEFAULT_MAX_RETRIES: u8 = 2;
+const GET_UPDATES_TIMEOUT_MARGIN: Duration = Duration::from_secs(10);
 
 /// A client for interacting with the Telegram Bot API.
 #[derive(Clone)]
 pub struct Client {
     host: String,
     http_client: HttpClient,
-    token: String,
+    token: Arc<RwLock<BotToken>>,
     max_retries: u8,
     max_retry_after: Option<u64>,
+    rate_limiter: Option<RateLimiter>,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    retry_policy: Option<Arc<dyn RetryPolicy>>,
+    chat_migration_handler: Option<ChatMigrationHandler>,
+    local_mode: bool,
+    observer: Option<Arc<dyn ClientObserver>>,
 }
 
+type ChatMigrationHandler = Arc<dyn Fn(ChatId, ChatPeerId) -> BoxFuture<'static, ()> + Send + Sync>;
+
 impl Client {
     /// Creates a new Telegram Bot API client with the provided bot token.
     ///
+    /// Use [`ClientBuilder`] to configure a proxy, timeouts and other settings.
+    ///
     /// # Arguments
     ///
     /// * `token` - A token associated with your bot.
     pub fn new<T>(token: T) -> Result<Self, ClientError>
     where
-        T: Into<String>,
+        T: Into<BotToken>,
     {
-        let client = {
-            #[cfg(feature = "webpki-roots")]
-            {
-                let root_cert_store = rustls::RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
-
-                let tls_config = rustls::ClientConfig::builder()
-                    .with_root_certificates(root_cert_store)
-                    .with_no_client_auth();
-
-                HttpClientBuilder::new()
-                    .tls_backend_preconfigured(tls_config)
-                    .build()
-                    .map_err(ClientError::BuildClient)?
-            }
-
-            #[cfg(not(feature = "webpki-roots"))]
-            {
-                HttpClientBuilder::new()
-                    .tls_backend_rustls()
-                    .build()
-                    .map_err(ClientError::BuildClient)?
-            }
-        };
-        Ok(Self::with_http_client(client, token))
+        ClientBuilder::new(token).build()
     }
 
     /// Creates a new Telegram Bot API client with a custom HTTP client and bot token.
@@ -73,14 +90,20 @@ impl Client {
     ///
     pub fn with_http_client<T>(http_client: HttpClient, token: T) -> Self
     where
-        T: Into<String>,
+        T: Into<BotToken>,
     {
         Self {
             http_client,
             host: String::from(DEFAULT_HOST),
-            token: token.into(),
+            token: Arc::new(RwLock::new(token.into())),
             max_retries: DEFAULT_MAX_RETRIES,
             max_retry_after: None,
+            rate_limiter: None,
+            interceptors: Vec::new(),
+            retry_policy: None,
+            chat_migration_handler: None,
+            local_mode: false,
+            observer: None,
         }
     }
 
@@ -119,10 +142,151 @@ impl Client {
         self
     }
 
+    /// Sets a policy for retrying requests failed due to transport or server errors.
+    ///
+    /// Requests failed due to flood control are retried according to
+    /// [`Self::with_max_retries`] and [`Self::with_max_retry_after`] regardless of the policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The retry policy; see [`crate::api::ExponentialBackoff`] for a default implementation.
+    ///
+    /// Such requests are not retried by default.
+    pub fn with_retry_policy<T>(mut self, value: T) -> Self
+    where
+        T: RetryPolicy + 'static,
+    {
+        self.retry_policy = Some(Arc::new(value));
+        self
+    }
+
+    /// Sets a new bot token.
+    ///
+    /// Clones of a client share the HTTP connection pool,
+    /// so use `client.clone().with_token(...)` to work with many bots;
+    /// see also [`crate::api::ClientPool`].
+    ///
+    /// The state of the rate limiter is shared between clones;
+    /// use [`Self::with_rate_limiter`] to set a separate one.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A token associated with the bot.
+    pub fn with_token<T>(mut self, value: T) -> Self
+    where
+        T: Into<BotToken>,
+    {
+        self.token = Arc::new(RwLock::new(value.into()));
+        self
+    }
+
+    /// Returns a client for another bot.
+    ///
+    /// Unlike [`Self::with_token`], the client gets a separate state of the rate limiter.
+    pub(super) fn for_bot(&self, token: BotToken) -> Self {
+        let mut client = self.clone().with_token(token);
+        client.rate_limiter = self.rate_limiter.as_ref().map(RateLimiter::with_new_state);
+        client
+    }
+
+    /// Returns the token of the bot.
+    pub(super) fn token(&self) -> BotToken {
+        self.token.read().unwrap().clone()
+    }
+
+    /// Replaces the token of the bot in the client and all its clones.
+    pub(super) fn replace_token(&self, value: BotToken) {
+        *self.token.write().unwrap() = value;
+    }
+
+    /// Sets an observer of method executions.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The observer.
+    pub fn with_observer<T>(mut self, value: T) -> Self
+    where
+        T: ClientObserver + 'static,
+    {
+        self.observer = Some(Arc::new(value));
+        self
+    }
+
+    /// Enables the local mode for a self-hosted Bot API server.
+    ///
+    /// In the local mode:
+    ///
+    /// * [`Self::download_file`] reads files with absolute paths from the local filesystem;
+    /// * files created using [`crate::types::InputFile::path`] are sent as `file://` URIs
+    ///   instead of uploading, including files attached to media groups and other JSON parameters.
+    ///
+    /// Use [`Self::with_host`] to set the address of the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Whether the local mode is enabled; default - `false`.
+    pub fn with_local_mode(mut self, value: bool) -> Self {
+        self.local_mode = value;
+        self
+    }
+
+    /// Enables transparent handling of group to supergroup migrations.
+    ///
+    /// When a method fails because its group has been migrated to a supergroup,
+    /// the client calls the handler and executes the method again for the supergroup.
+    /// Methods containing a stream are not executed again.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - A function which receives the identifiers of the group and the supergroup;
+    ///   can be used to update stored identifiers.
+    ///
+    /// Disabled by default.
+    pub fn with_chat_migration_handler<H, F>(mut self, handler: H) -> Self
+    where
+        H: Fn(ChatId, ChatPeerId) -> F + Send + Sync + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.chat_migration_handler = Some(Arc::new(move |from_chat_id, to_chat_id| {
+            Box::pin(handler(from_chat_id, to_chat_id))
+        }));
+        self
+    }
+
+    /// Enables proactive rate limiting of sending methods.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The rate limiter to use; see [`RateLimiter`] for details.
+    ///
+    /// Disabled by default.
+    pub fn with_rate_limiter(mut self, value: RateLimiter) -> Self {
+        self.rate_limiter = Some(value);
+        self
+    }
+
+    /// Adds an interceptor to the chain around [`Self::execute`].
+    ///
+    /// Interceptors are called in the order they were added.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The interceptor to add.
+    pub fn with_interceptor<T>(mut self, value: T) -> Self
+    where
+        T: Interceptor + 'static,
+    {
+        self.interceptors.push(Arc::new(value));
+        self
+    }
+
     /// Downloads a file.
     ///
     /// Use [`crate::types::GetFile`] method to get a value for the `file_path` argument.
     ///
+    /// When the local mode is enabled and the `file_path` is absolute,
+    /// the file is read from the local filesystem; see [`Self::with_local_mode`].
+    ///
     /// # Arguments
     ///
     /// * `file_path` - The path to the file to be downloaded.
@@ -144,24 +308,232 @@ impl Client {
     pub async fn download_file<P>(
         &self,
         file_path: P,
-    ) -> Result<impl Stream<Item = Result<Bytes, HttpError>> + use<P>, DownloadFileError>
+    ) -> Result<impl Stream<Item = Result<Bytes, DownloadFileError>> + use<P>, DownloadFileError>
     where
         P: AsRef<str>,
     {
-        let file_path = file_path.as_ref();
+        self.download_file_from(file_path.as_ref(), 0).await
+    }
+
+    /// Downloads a file to a filesystem.
+    ///
+    /// The file is written to a temporary file `<path>.part` first,
+    /// which is renamed to `path` when the download is completed and the size is verified.
+    /// When the temporary file already exists,
+    /// the download is resumed from its end using an HTTP `Range` request.
+    /// The temporary file is kept when the download is interrupted by a network error
+    /// and removed on any other error.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - A `file_id` or a [`crate::types::File`] to download.
+    /// * `path` - The path to save the file to.
+    /// * `options` - The options of the download.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn download_to_path() {
+    /// use tgbot::api::{Client, DownloadOptions};
+    /// let api = Client::new("token").unwrap();
+    /// let options = DownloadOptions::default().with_max_size(20 * 1024 * 1024);
+    /// let file = api.download_to_path("file-id", "file.bin", options).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn download_to_path<S, P>(
+        &self,
+        source: S,
+        path: P,
+        options: DownloadOptions,
+    ) -> Result<TelegramFile, DownloadFileError>
+    where
+        S: Into<DownloadSource>,
+        P: AsRef<Path>,
+    {
+        let file = self.get_download_file(source.into(), &options).await?;
+        let path = path.as_ref();
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".part");
+        let temp_path = PathBuf::from(temp_path);
+        let mut temp_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&temp_path)
+            .await
+            .map_err(DownloadFileError::Io)?;
+        let mut offset = temp_file.metadata().await.map_err(DownloadFileError::Io)?.len();
+        let expected_size = get_file_size(&file);
+        if expected_size.is_some_and(|expected_size| offset > expected_size) {
+            debug!(
+                "Temporary file {} is larger than expected, truncating",
+                temp_path.display()
+            );
+            temp_file.set_len(0).await.map_err(DownloadFileError::Io)?;
+            offset = 0;
+        }
+        let result = async {
+            let size = if expected_size == Some(offset) {
+                offset
+            } else {
+                self.write_download(&file, &mut temp_file, offset, &options).await?
+            };
+            temp_file.sync_all().await.map_err(DownloadFileError::Io)?;
+            verify_file_size(&file, size)
+        }
+        .await;
+        drop(temp_file);
+        match result {
+            Ok(()) => {
+                fs::rename(&temp_path, path).await.map_err(DownloadFileError::Io)?;
+                Ok(file)
+            }
+            // The temporary file is kept to resume the download later
+            Err(err @ DownloadFileError::Http(_)) => Err(err),
+            Err(err) => {
+                if let Err(remove_err) = fs::remove_file(&temp_path).await {
+                    debug!("Could not remove temporary file {}: {remove_err}", temp_path.display());
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Downloads a file to a writer.
+    ///
+    /// An interrupted download is resumed using an HTTP `Range` request.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - A `file_id` or a [`crate::types::File`] to download.
+    /// * `writer` - The writer to write the file to.
+    /// * `options` - The options of the download.
+    pub async fn download_to_writer<S, W>(
+        &self,
+        source: S,
+        writer: &mut W,
+        options: DownloadOptions,
+    ) -> Result<TelegramFile, DownloadFileError>
+    where
+        S: Into<DownloadSource>,
+        W: AsyncWrite + Unpin,
+    {
+        let file = self.get_download_file(source.into(), &options).await?;
+        let size = self.write_download(&file, writer, 0, &options).await?;
+        writer.flush().await.map_err(DownloadFileError::Io)?;
+        verify_file_size(&file, size)?;
+        Ok(file)
+    }
+
+    async fn get_download_file(
+        &self,
+        source: DownloadSource,
+        options: &DownloadOptions,
+    ) -> Result<TelegramFile, DownloadFileError> {
+        let file = match source {
+            DownloadSource::File(file) if file.file_path.is_some() => file,
+            DownloadSource::File(TelegramFile { file_id, .. }) | DownloadSource::FileId(file_id) => {
+                self.execute(GetFile::new(file_id)).await?
+            }
+        };
+        if let (Some(file_size), Some(max_size)) = (get_file_size(&file), options.max_size())
+            && file_size > max_size
+        {
+            return Err(DownloadFileError::FileTooLarge { max_size });
+        }
+        Ok(file)
+    }
+
+    async fn write_download<W>(
+        &self,
+        file: &TelegramFile,
+        writer: &mut W,
+        offset: u64,
+        options: &DownloadOptions,
+    ) -> Result<u64, DownloadFileError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let file_path = file.file_path.as_deref().ok_or(DownloadFileError::MissingFilePath)?;
+        let mut size = offset;
+        let mut attempt = 0;
+        loop {
+            let result = async {
+                let stream = self.download_file_from(file_path, size).await?;
+                let mut stream = std::pin::pin!(stream);
+                while let Some(chunk) = stream.next().await {
+                    let chunk = chunk?;
+                    size += chunk.len() as u64;
+                    if let Some(max_size) = options.max_size()
+                        && size > max_size
+                    {
+                        return Err(DownloadFileError::FileTooLarge { max_size });
+                    }
+                    writer.write_all(&chunk).await.map_err(DownloadFileError::Io)?;
+                }
+                Ok(())
+            }
+            .await;
+            match result {
+                Ok(()) => return Ok(size),
+                Err(DownloadFileError::Http(err)) if attempt < options.max_resume_attempts() => {
+                    attempt += 1;
+                    let delay = options.get_resume_delay(attempt);
+                    debug!(
+                        "Download has been interrupted ({err}), resuming from {size} byte(s), attempt {attempt} in {delay:?}"
+                    );
+                    sleep(delay).await;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    async fn download_file_from(
+        &self,
+        file_path: &str,
+        offset: u64,
+    ) -> Result<impl Stream<Item = Result<Bytes, DownloadFileError>> + use<>, DownloadFileError> {
+        if self.local_mode && Path::new(file_path).is_absolute() {
+            debug!("Reading file from {file_path}");
+            let mut file = File::open(file_path).await.map_err(DownloadFileError::Io)?;
+            if offset > 0 {
+                file.seek(SeekFrom::Start(offset))
+                    .await
+                    .map_err(DownloadFileError::Io)?;
+            }
+            return Ok(Either::Left(ReaderStream::new(file).map_err(DownloadFileError::Io)));
+        }
         debug!("Downloading file from {file_path}");
         let payload = Payload::empty(file_path);
-        let url = payload.build_url(&format!("{}/file", self.host), &self.token);
-        let rep = self.http_client.get(&url).send().await?;
+        let token = self.token();
+        let url = payload.build_url(&format!("{}/file", self.host), token.expose_secret());
+        let mut request = self.http_client.get(&url);
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={offset}-"));
+        }
+        let rep = request.send().await.map_err(|err| token.redact_error(err))?;
         let status = rep.status();
         if !status.is_success() {
-            Err(DownloadFileError::Response {
+            return Err(DownloadFileError::Response {
                 status: status.as_u16(),
-                text: rep.text().await?,
-            })
-        } else {
-            Ok(rep.bytes_stream())
+                text: rep.text().await.map_err(|err| token.redact_error(err))?,
+            });
         }
+        // The server may ignore the Range header and return the whole file
+        let mut skip = if status == StatusCode::PARTIAL_CONTENT {
+            0
+        } else {
+            offset
+        };
+        Ok(Either::Right(
+            rep.bytes_stream()
+                .map_err(move |err| DownloadFileError::Http(token.redact_error(err)))
+                .map_ok(move |mut chunk| {
+                    let skipped = skip.min(chunk.len() as u64);
+                    skip -= skipped;
+                    chunk.split_off(skipped as usize)
+                }),
+        ))
     }
 
     /// Executes a method.
@@ -172,90 +544,276 @@ impl Client {
     ///
     /// # Notes
     ///
-    /// The client will not retry a request on a timeout error if the request is not cloneable
-    /// (e.g. contains a stream).
+    /// The client will not retry a request if the request is not cloneable
+    /// (e.g. contains a file created from a reader).
+    ///
+    /// A [`crate::types::GetUpdates`] request gets an HTTP timeout
+    /// derived from its `timeout` parameter.
     pub async fn execute<M>(&self, method: M) -> Result<M::Response, ExecuteError>
     where
         M: Method,
         M::Response: DeserializeOwned + Send + 'static,
     {
-        let request = method
-            .into_payload()
-            .into_http_request_builder(&self.http_client, &self.host, &self.token)?;
-        let response = match send_request_retry(Box::new(request)).await? {
-            RetryResponse::Ok(response) => response,
-            RetryResponse::Retry {
-                mut request,
-                mut response,
-                mut retry_after,
-            } => {
-                for i in 0..self.max_retries {
-                    if let Some(max_retry_after) = self.max_retry_after {
-                        retry_after = retry_after.min(max_retry_after);
-                    }
-                    debug!("Retry attempt {i}, sleeping for {retry_after} second(s)");
-                    sleep(Duration::from_secs(retry_after)).await;
-                    match send_request_retry(request).await? {
-                        RetryResponse::Ok(new_response) => {
-                            response = new_response;
-                            break;
-                        }
-                        RetryResponse::Retry {
-                            request: new_request,
-                            response: new_response,
-                            retry_after: new_retry_after,
-                        } => {
-                            request = new_request;
-                            response = new_response;
-                            retry_after = new_retry_after;
-                        }
-                    }
-                }
-                response
+        self.execute_with(method, ExecuteOptions::default()).await
+    }
+
+    /// Executes a method with options.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The method to execute.
+    /// * `options` - A deadline, a cancellation token and a number of retries;
+    ///   see [`ExecuteOptions`] for details.
+    pub async fn execute_with<M>(&self, method: M, options: ExecuteOptions) -> Result<M::Response, ExecuteError>
+    where
+        M: Method,
+        M::Response: DeserializeOwned + Send + 'static,
+    {
+        let payload = method.into_payload();
+        let Some(observer) = &self.observer else {
+            return self.execute_payload(payload, &options).await;
+        };
+        let method_name = payload.method_name().to_string();
+        observer.on_request_start(&method_name);
+        let started_at = Instant::now();
+        let result = self.execute_payload(payload, &options).await;
+        observer.on_request_complete(&method_name, started_at.elapsed(), result.as_ref().err());
+        result
+    }
+
+    async fn execute_payload<T>(&self, payload: Payload, options: &ExecuteOptions) -> Result<T, ExecuteError>
+    where
+        T: DeserializeOwned,
+    {
+        let result = async {
+            let max_retries = options.max_retries().unwrap_or(self.max_retries);
+            let response = Next::new(self, &self.interceptors, max_retries).run(payload).await?;
+            Ok(serde_json::from_value(response.into_result()?)?)
+        };
+        let result = match options.deadline() {
+            Some(deadline) => {
+                Either::Left(async move { timeout_at(deadline, result).await.unwrap_or(Err(ExecuteError::Timeout)) })
             }
+            None => Either::Right(result),
         };
-        Ok(response.into_result()?)
+        match options.cancellation_token() {
+            Some(cancellation_token) => cancellation_token
+                .run_until_cancelled(result)
+                .await
+                .unwrap_or(Err(ExecuteError::Cancelled)),
+            None => result.await,
+        }
     }
-}
 
-enum RetryResponse<T> {
-    Ok(Response<T>),
-    Retry {
-        request: Box<HttpRequestBuilder>,
-        response: Response<T>,
-        retry_after: u64,
-    },
-}
+    /// Executes methods concurrently.
+    ///
+    /// Returns results in the order of the methods.
+    ///
+    /// # Arguments
+    ///
+    /// * `methods` - The methods to execute.
+    /// * `concurrency_limit` - Maximum number of methods executed simultaneously; at least 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn execute_all() {
+    /// use tgbot::{api::Client, types::GetChatMember};
+    /// let api = Client::new("token").unwrap();
+    /// let methods = [1, 2, 3].map(|user_id| GetChatMember::new(-1, user_id));
+    /// for result in api.execute_all(methods, 2).await {
+    ///     let member = result.unwrap();
+    /// }
+    /// # }
+    /// ```
+    pub async fn execute_all<I, M>(
+        &self,
+        methods: I,
+        concurrency_limit: usize,
+    ) -> Vec<Result<M::Response, ExecuteError>>
+    where
+        I: IntoIterator<Item = M>,
+        M: Method,
+        M::Response: DeserializeOwned + Send + 'static,
+    {
+        stream::iter(methods)
+            .map(|method| self.execute(method))
+            .buffered(concurrency_limit.max(1))
+            .collect()
+            .await
+    }
+
+    /// Executes methods concurrently.
+    ///
+    /// Returns a stream of results in the order of completion,
+    /// each result is tagged with the index of its method.
+    ///
+    /// # Arguments
+    ///
+    /// * `methods` - The methods to execute.
+    /// * `concurrency_limit` - Maximum number of methods executed simultaneously; at least 1.
+    pub fn execute_stream<'a, I, M>(
+        &'a self,
+        methods: I,
+        concurrency_limit: usize,
+    ) -> impl Stream<Item = (usize, Result<M::Response, ExecuteError>)> + 'a
+    where
+        I: IntoIterator<Item = M>,
+        I::IntoIter: 'a,
+        M: Method + 'a,
+        M::Response: DeserializeOwned + Send + 'static,
+    {
+        stream::iter(methods.into_iter().enumerate())
+            .map(move |(idx, method)| async move { (idx, self.execute(method).await) })
+            .buffer_unordered(concurrency_limit.max(1))
+    }
+
+    pub(super) async fn send_payload(
+        &self,
+        mut payload: Payload,
+        max_retries: u8,
+    ) -> Result<ReceivedResponse, ExecuteError> {
+        if self.local_mode {
+            payload.use_file_uris()?;
+        }
+        let Some(chat_migration_handler) = &self.chat_migration_handler else {
+            return self.send_payload_retry(payload, max_retries).await;
+        };
+        let retained_payload = payload.try_clone();
+        let received = self.send_payload_retry(payload, max_retries).await?;
+        match (retained_payload, &received.response) {
+            (Some(mut payload), Response::Error(err)) => match (payload.chat_id(), err.migrate_to_chat_id()) {
+                (Some(from_chat_id), Some(to_chat_id)) => {
+                    debug!("Chat {from_chat_id} has been migrated to {to_chat_id}, sending request again");
+                    payload.set_chat_id(to_chat_id)?;
+                    chat_migration_handler(from_chat_id, to_chat_id.into()).await;
+                    self.send_payload_retry(payload, max_retries).await
+                }
+                _ => Ok(received),
+            },
+            _ => Ok(received),
+        }
+    }
 
-async fn send_request_retry<T>(request: Box<HttpRequestBuilder>) -> Result<RetryResponse<T>, ExecuteError>
-where
-    T: DeserializeOwned,
-{
-    Ok(match request.try_clone() {
-        Some(try_request) => {
-            let response = send_request(try_request).await?;
-            match response.retry_after() {
-                Some(retry_after) => RetryResponse::Retry {
-                    request,
-                    response,
-                    retry_after,
+    async fn send_payload_retry(&self, payload: Payload, max_retries: u8) -> Result<ReceivedResponse, ExecuteError> {
+        let rate_limit = self.rate_limiter.as_ref().zip(get_rate_limited_chat_id(&payload));
+        let started_at = Instant::now();
+        let mut flood_retries = 0;
+        let mut attempt = 0;
+        loop {
+            if let Some((rate_limiter, chat_id)) = &rate_limit {
+                rate_limiter.acquire(chat_id).await;
+            }
+            let Some(try_payload) = payload.try_clone() else {
+                debug!("Could not clone payload, sending request without retry");
+                let token = self.token();
+                return send_request(self.build_request(payload, &token)?, &token).await;
+            };
+            let token = self.token();
+            let result = send_request(self.build_request(try_payload, &token)?, &token).await;
+            let delay = match result.as_ref().map(|received| &received.response) {
+                Ok(Response::Success(_)) => None,
+                Ok(Response::Error(err)) => match err.retry_after() {
+                    Some(retry_after) => {
+                        if flood_retries < max_retries {
+                            flood_retries += 1;
+                            let retry_after = match self.max_retry_after {
+                                Some(max_retry_after) => retry_after.min(max_retry_after),
+                                None => retry_after,
+                            };
+                            debug!("Retry attempt {flood_retries}, sleeping for {retry_after} second(s)");
+                            let retry_after = Duration::from_secs(retry_after);
+                            if let Some(observer) = &self.observer {
+                                observer.on_flood_wait(payload.method_name(), retry_after);
+                            }
+                            Some(retry_after)
+                        } else {
+                            None
+                        }
+                    }
+                    None => {
+                        attempt += 1;
+                        self.get_retry_delay(&payload, &ExecuteError::Response(err.clone()), attempt, started_at)
+                    }
                 },
-                None => RetryResponse::Ok(response),
+                Err(err) => {
+                    attempt += 1;
+                    self.get_retry_delay(&payload, err, attempt, started_at)
+                }
+            };
+            match delay {
+                Some(delay) => sleep(delay).await,
+                None => return result,
             }
         }
-        None => {
-            debug!("Could not clone builder, sending request without retry");
-            RetryResponse::Ok(send_request(*request).await?)
+    }
+
+    fn build_request(&self, payload: Payload, token: &BotToken) -> Result<HttpRequestBuilder, PayloadError> {
+        let timeout = get_updates_timeout(&payload);
+        let request = payload.into_http_request_builder(&self.http_client, &self.host, token.expose_secret())?;
+        Ok(match timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        })
+    }
+
+    fn get_retry_delay(
+        &self,
+        payload: &Payload,
+        err: &ExecuteError,
+        attempt: u32,
+        started_at: Instant,
+    ) -> Option<Duration> {
+        let delay = self
+            .retry_policy
+            .as_ref()
+            .and_then(|retry_policy| retry_policy.get_delay(payload.method_name(), err, attempt, started_at.elapsed()));
+        if let Some(delay) = delay {
+            debug!("An error has occurred ({err}), retry attempt {attempt} in {delay:?}");
+            if let Some(observer) = &self.observer {
+                observer.on_retry(payload.method_name(), attempt, delay, err);
+            }
         }
-    })
+        delay
+    }
+}
+
+fn get_updates_timeout(payload: &Payload) -> Option<Duration> {
+    if payload.method_name() != "getUpdates" {
+        return None;
+    }
+    let timeout = payload
+        .get_field("timeout")
+        .and_then(|x| x.as_u64())
+        .unwrap_or_default();
+    Some(Duration::from_secs(timeout) + GET_UPDATES_TIMEOUT_MARGIN)
 }
 
-async fn send_request<T>(request: HttpRequestBuilder) -> Result<Response<T>, ExecuteError>
-where
-    T: DeserializeOwned,
-{
-    let response = request.send().await?;
-    Ok(response.json::<Response<T>>().await?)
+/// Represents a response along with its body as received from the server.
+pub(super) struct ReceivedResponse {
+    pub(super) response: Response<JsonValue>,
+    pub(super) body: Box<RawJsonValue>,
+}
+
+async fn send_request(request: HttpRequestBuilder, token: &BotToken) -> Result<ReceivedResponse, ExecuteError> {
+    let redact = |err| ExecuteError::Http(token.redact_error(err));
+    let response = request.send().await.map_err(redact)?;
+    match response.error_for_status_ref() {
+        Err(err) if response.status().is_server_error() => {
+            let body = response.bytes().await.map_err(redact)?;
+            serde_json::from_slice::<Box<RawJsonValue>>(&body)
+                .and_then(|body| {
+                    let response = serde_json::from_str(body.get())?;
+                    Ok(ReceivedResponse { response, body })
+                })
+                .map_err(|_| redact(err))
+        }
+        _ => {
+            let body = response.json::<Box<RawJsonValue>>().await.map_err(redact)?;
+            let response = serde_json::from_str(body.get())?;
+            Ok(ReceivedResponse { response, body })
+        }
+    }
 }
 
 impl fmt::Debug for Client {
@@ -277,17 +835,57 @@ pub trait Method {
     fn into_payload(self) -> Payload;
 }
 
+/// Represents an executor of API methods.
+///
+/// Implemented by [`Client`] and [`crate::api::FakeApi`];
+/// use it instead of a concrete client to test code without sending requests.
+pub trait Api: Send + Sync {
+    /// Executes a method.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The method to execute.
+    fn execute<M>(&self, method: M) -> impl Future<Output = Result<M::Response, ExecuteError>> + Send
+    where
+        M: Method + Send + 'static,
+        M::Response: DeserializeOwned + Send + 'static;
+}
+
+impl Api for Client {
+    fn execute<M>(&self, method: M) -> impl Future<Output = Result<M::Response, ExecuteError>> + Send
+    where
+        M: Method + Send + 'static,
+        M::Response: DeserializeOwned + Send + 'static,
+    {
+        Client::execute(self, method)
+    }
+}
+
 /// Represents general errors that can occur while working with the Telegram Bot API client.
 #[derive(Debug)]
 pub enum ClientError {
     /// An error indicating a failure to build an HTTP client.
     BuildClient(HttpError),
+    /// An error indicating a failure to build a runtime for the blocking client.
+    BuildRuntime(IoError),
+    /// An error indicating a missing or invalid environment variable.
+    EnvVar {
+        /// The name of the variable.
+        name: &'static str,
+        /// The error.
+        error: VarError,
+    },
+    /// An error indicating an invalid root certificate.
+    InvalidCertificate(Box<dyn Error + Send + Sync>),
 }
 
 impl Error for ClientError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         Some(match self {
             ClientError::BuildClient(err) => err,
+            ClientError::BuildRuntime(err) => err,
+            ClientError::EnvVar { error, .. } => error,
+            ClientError::InvalidCertificate(err) => err.as_ref(),
         })
     }
 }
@@ -296,6 +894,9 @@ impl fmt::Display for ClientError {
     fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
         match self {
             ClientError::BuildClient(err) => write!(out, "can not build HTTP client: {err}"),
+            ClientError::BuildRuntime(err) => write!(out, "can not build runtime: {err}"),
+            ClientError::EnvVar { name, error } => write!(out, "can not read environment variable {name}: {error}"),
+            ClientError::InvalidCertificate(err) => write!(out, "invalid root certificate: {err}"),
         }
     }
 }
@@ -304,8 +905,26 @@ impl fmt::Display for ClientError {
 /// to download a file using the Telegram Bot API client.
 #[derive(Debug)]
 pub enum DownloadFileError {
+    /// An error indicating a failure to get a path of the file.
+    Execute(ExecuteError),
+    /// The size of the downloaded file differs from the size reported by the server.
+    FileSizeMismatch {
+        /// The size reported by the server.
+        expected: u64,
+        /// The size of the downloaded file.
+        actual: u64,
+    },
+    /// The file exceeds the maximum size.
+    FileTooLarge {
+        /// The maximum size in bytes.
+        max_size: u64,
+    },
     /// An error indicating a failure to send an HTTP request.
     Http(HttpError),
+    /// An error indicating a failure to read or write a file.
+    Io(IoError),
+    /// The server did not return a path of the file.
+    MissingFilePath,
     /// An error received from the server in response to the download request.
     Response {
         /// The HTTP status code received in the response.
@@ -315,16 +934,35 @@ pub enum DownloadFileError {
     },
 }
 
+impl From<ExecuteError> for DownloadFileError {
+    fn from(err: ExecuteError) -> Self {
+        Self::Execute(err)
+    }
+}
+
 impl From<HttpError> for DownloadFileError {
     fn from(err: HttpError) -> Self {
         Self::Http(err)
     }
 }
 
+fn get_file_size(file: &TelegramFile) -> Option<u64> {
+    file.file_size.and_then(|x| u64::try_from(x).ok())
+}
+
+fn verify_file_size(file: &TelegramFile, actual: u64) -> Result<(), DownloadFileError> {
+    match get_file_size(file) {
+        Some(expected) if expected != actual => Err(DownloadFileError::FileSizeMismatch { expected, actual }),
+        _ => Ok(()),
+    }
+}
+
 impl Error for DownloadFileError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
+            DownloadFileError::Execute(err) => Some(err),
             DownloadFileError::Http(err) => Some(err),
+            DownloadFileError::Io(err) => Some(err),
             _ => None,
         }
     }
@@ -333,7 +971,16 @@ impl Error for DownloadFileError {
 impl fmt::Display for DownloadFileError {
     fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            DownloadFileError::Execute(err) => write!(out, "failed to get file: {err}"),
+            DownloadFileError::FileSizeMismatch { expected, actual } => {
+                write!(out, "file size mismatch: expected={expected} actual={actual}")
+            }
+            DownloadFileError::FileTooLarge { max_size } => {
+                write!(out, "file is too large: max_size={max_size}")
+            }
             DownloadFileError::Http(err) => write!(out, "failed to download file: {err}"),
+            DownloadFileError::Io(err) => write!(out, "failed to read or write file: {err}"),
+            DownloadFileError::MissingFilePath => write!(out, "file path is missing"),
             DownloadFileError::Response { status, text } => {
                 write!(out, "failed to download file: status={status} text={text}")
             }
@@ -345,22 +992,44 @@ impl fmt::Display for DownloadFileError {
 /// of a method using the Telegram Bot API client.
 #[derive(Debug, derive_more::From)]
 pub enum ExecuteError {
+    /// The execution has been cancelled using a cancellation token.
+    #[from(ignore)]
+    Cancelled,
     /// An error indicating a failure to send an HTTP request.
     Http(HttpError),
+    /// An error indicating a failure to deserialize a result of the method.
+    Json(JsonError),
     /// An error indicating a failure to build an HTTP request payload.
     Payload(PayloadError),
     /// An error received from the Telegram server in response to the execution request.
     Response(ResponseError),
+    /// The deadline of the execution has been reached.
+    #[from(ignore)]
+    Timeout,
+}
+
+impl ExecuteError {
+    /// Returns a kind of the Telegram error.
+    ///
+    /// Returns `None` when the error is not received from the Telegram server.
+    pub fn response_error_kind(&self) -> Option<ResponseErrorKind> {
+        match self {
+            ExecuteError::Response(err) => Some(err.kind()),
+            _ => None,
+        }
+    }
 }
 
 impl Error for ExecuteError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         use self::ExecuteError::*;
-        Some(match self {
-            Http(err) => err,
-            Payload(err) => err,
-            Response(err) => err,
-        })
+        match self {
+            Cancelled | Timeout => None,
+            Http(err) => Some(err),
+            Json(err) => Some(err),
+            Payload(err) => Some(err),
+            Response(err) => Some(err),
+        }
     }
 }
 
@@ -371,9 +1040,12 @@ impl fmt::Display for ExecuteError {
             out,
             "failed to execute method: {}",
             match self {
+                Cancelled => String::from("cancelled"),
                 Http(err) => err.to_string(),
+                Json(err) => err.to_string(),
                 Payload(err) => err.to_string(),
                 Response(err) => err.to_string(),
+                Timeout => String::from("deadline has been reached"),
             }
         )
     }
@@ -386,15 +1058,34 @@ mod tests {
     #[test]
     fn api() {
         let client = Client::new("token").unwrap();
-        assert_eq!(client.token, "token");
+        assert_eq!(client.token().expose_secret(), "token");
         assert_eq!(client.host, DEFAULT_HOST);
 
         let client = Client::new("token")
             .unwrap()
             .with_host("https://example.com")
             .with_max_retries(1);
-        assert_eq!(client.token, "token");
+        assert_eq!(client.token().expose_secret(), "token");
         assert_eq!(client.host, "https://example.com");
         assert_eq!(client.max_retries, 1);
     }
+
+    #[test]
+    fn get_updates_http_timeout() {
+        use crate::types::{Close, GetUpdates};
+
+        let payload = GetUpdates::default().into_payload();
+        assert_eq!(get_updates_timeout(&payload), Some(GET_UPDATES_TIMEOUT_MARGIN));
+
+        let payload = GetUpdates::default()
+            .with_timeout(Duration::from_secs(30))
+            .into_payload();
+        assert_eq!(
+            get_updates_timeout(&payload),
+            Some(Duration::from_secs(30) + GET_UPDATES_TIMEOUT_MARGIN)
+        );
+
+        let payload = Close.into_payload();
+        assert_eq!(get_updates_timeout(&payload), None);
+    }
 }
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use crate::types::File;
+
+const DEFAULT_MAX_RESUME_ATTEMPTS: u32 = 3;
+const DEFAULT_RESUME_DELAY: Duration = Duration::from_millis(500);
+
+/// Represents a file to download.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DownloadSource {
+    /// Identifier of the file; the path is obtained using [`crate::types::GetFile`].
+    FileId(String),
+    /// The file obtained using [`crate::types::GetFile`].
+    ///
+    /// When the path of the file is missing, it is obtained again.
+    File(File),
+}
+
+impl From<&str> for DownloadSource {
+    fn from(value: &str) -> Self {
+        Self::FileId(String::from(value))
+    }
+}
+
+impl From<String> for DownloadSource {
+    fn from(value: String) -> Self {
+        Self::FileId(value)
+    }
+}
+
+impl From<File> for DownloadSource {
+    fn from(value: File) -> Self {
+        Self::File(value)
+    }
+}
+
+/// Represents options of a file download.
+///
+/// Used by [`crate::api::Client::download_to_path`] and [`crate::api::Client::download_to_writer`].
+#[derive(Clone, Copy, Debug)]
+pub struct DownloadOptions {
+    max_size: Option<u64>,
+    max_resume_attempts: u32,
+    resume_delay: Duration,
+}
+
+impl DownloadOptions {
+    /// Sets a new maximum size of a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Maximum size in bytes; default - unlimited.
+    pub fn with_max_size(mut self, value: u64) -> Self {
+        self.max_size = Some(value);
+        self
+    }
+
+    /// Returns the maximum size of a file.
+    pub fn max_size(&self) -> Option<u64> {
+        self.max_size
+    }
+
+    /// Sets a new maximum number of attempts to resume an interrupted download.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Maximum number of attempts; default - 3.
+    pub fn with_max_resume_attempts(mut self, value: u32) -> Self {
+        self.max_resume_attempts = value;
+        self
+    }
+
+    /// Returns the maximum number of attempts to resume an interrupted download.
+    pub fn max_resume_attempts(&self) -> u32 {
+        self.max_resume_attempts
+    }
+
+    /// Sets a new delay before the first attempt to resume an interrupted download.
+    ///
+    /// The delay is doubled after each attempt.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The delay; default - 500 milliseconds.
+    pub fn with_resume_delay(mut self, value: Duration) -> Self {
+        self.resume_delay = value;
+        self
+    }
+
+    /// Returns the delay before the first attempt to resume an interrupted download.
+    pub fn resume_delay(&self) -> Duration {
+        self.resume_delay
+    }
+
+    pub(super) fn get_resume_delay(&self, attempt: u32) -> Duration {
+        self.resume_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+    }
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            max_size: None,
+            max_resume_attempts: DEFAULT_MAX_RESUME_ATTEMPTS,
+            resume_delay: DEFAULT_RESUME_DELAY,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resume_delay() {
+        let options = DownloadOptions::default().with_resume_delay(Duration::from_secs(1));
+        assert_eq!(options.get_resume_delay(1), Duration::from_secs(1));
+        assert_eq!(options.get_resume_delay(2), Duration::from_secs(2));
+        assert_eq!(options.get_resume_delay(3), Duration::from_secs(4));
+        assert_eq!(options.get_resume_delay(100), Duration::from_secs(u64::from(u32::MAX)));
+    }
+}
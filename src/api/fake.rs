@@ -0,0 +1,294 @@
+use std::{
+    any::{Any, TypeId, type_name},
+    collections::{HashMap, VecDeque},
+    fmt,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value as JsonValue;
+
+use super::{
+    client::{Api, ExecuteError, Method},
+    payload::Payload,
+    raw::RawMethod,
+};
+use crate::types::ResponseError;
+
+/// An implementation of [`Api`] which does not send requests.
+///
+/// Executed methods are recorded, responses are taken from a queue of scripted responses.
+/// Responses are queued per type of the method, or per name for [`RawMethod`].
+/// Clones of a `FakeApi` share the same state.
+///
+/// # Panics
+///
+/// Executing a method panics when there is no scripted response for it.
+///
+/// # Example
+///
+/// ```
+/// # async fn fake_api() {
+/// use tgbot::{
+///     api::{Api, FakeApi},
+///     types::{Message, MessageData, PrivateChat, SendMessage, Text, User},
+/// };
+///
+/// async fn greet<A: Api>(api: &A) {
+///     api.execute(SendMessage::new(1, "hello")).await.unwrap();
+/// }
+///
+/// let api = FakeApi::default();
+/// api.push_response::<SendMessage>(Message::new(
+///     1,
+///     0,
+///     PrivateChat::new(1, "John"),
+///     MessageData::Text(Text::from("hello")),
+///     User::new(2, "Bot", true),
+/// ));
+/// greet(&api).await;
+/// let mut executed = api.take_executed();
+/// assert!(executed[0].downcast_ref::<SendMessage>().is_some());
+/// let payload = executed.remove(0).into_payload();
+/// assert_eq!(payload.get_field("text").unwrap(), "hello");
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FakeApi {
+    state: Arc<Mutex<FakeApiState>>,
+}
+
+#[derive(Debug, Default)]
+struct FakeApiState {
+    executed: Vec<ExecutedMethod>,
+    responses: HashMap<ResponseKey, VecDeque<Result<JsonValue, ResponseError>>>,
+}
+
+#[derive(Debug, Eq, Hash, PartialEq)]
+enum ResponseKey {
+    Type(TypeId),
+    Name(String),
+}
+
+impl FakeApi {
+    /// Adds a successful response to the queue of the method.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The result of the method.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the value can not be serialized.
+    pub fn push_response<M>(&self, value: M::Response)
+    where
+        M: Method + 'static,
+        M::Response: Serialize,
+    {
+        let value = serde_json::to_value(value).expect("Failed to serialize a response");
+        self.push(ResponseKey::Type(TypeId::of::<M>()), Ok(value));
+    }
+
+    /// Adds an error response to the queue of the method.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The error.
+    pub fn push_error<M>(&self, value: ResponseError)
+    where
+        M: Method + 'static,
+    {
+        self.push(ResponseKey::Type(TypeId::of::<M>()), Err(value));
+    }
+
+    /// Adds a successful response to the queue of a [`RawMethod`] with the given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the method, e.g. `getMe`.
+    /// * `value` - The result of the method.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the value can not be serialized.
+    pub fn push_raw_response<A, B>(&self, name: A, value: B)
+    where
+        A: Into<String>,
+        B: Serialize,
+    {
+        let value = serde_json::to_value(value).expect("Failed to serialize a response");
+        self.push(ResponseKey::Name(name.into()), Ok(value));
+    }
+
+    /// Adds an error response to the queue of a [`RawMethod`] with the given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the method.
+    /// * `value` - The error.
+    pub fn push_raw_error<T>(&self, name: T, value: ResponseError)
+    where
+        T: Into<String>,
+    {
+        self.push(ResponseKey::Name(name.into()), Err(value));
+    }
+
+    /// Returns the executed methods and clears the list.
+    pub fn take_executed(&self) -> Vec<ExecutedMethod> {
+        std::mem::take(&mut self.state.lock().unwrap().executed)
+    }
+
+    fn push(&self, key: ResponseKey, value: Result<JsonValue, ResponseError>) {
+        self.state
+            .lock()
+            .unwrap()
+            .responses
+            .entry(key)
+            .or_default()
+            .push_back(value);
+    }
+}
+
+impl Api for FakeApi {
+    fn execute<M>(&self, method: M) -> impl Future<Output = Result<M::Response, ExecuteError>> + Send
+    where
+        M: Method + Send + 'static,
+        M::Response: DeserializeOwned + Send + 'static,
+    {
+        let key = match (&method as &dyn Any).downcast_ref::<RawMethod<M::Response>>() {
+            Some(method) => ResponseKey::Name(method.name().to_string()),
+            None => ResponseKey::Type(TypeId::of::<M>()),
+        };
+        let mut state = self.state.lock().unwrap();
+        let response = state
+            .responses
+            .get_mut(&key)
+            .and_then(|responses| responses.pop_front());
+        let Some(response) = response else {
+            drop(state);
+            panic!("No response for {}", method.into_payload().method_name());
+        };
+        state.executed.push(ExecutedMethod::new(method));
+        async move {
+            match response {
+                Ok(value) => Ok(serde_json::from_value(value)?),
+                Err(err) => Err(ExecuteError::Response(err)),
+            }
+        }
+    }
+}
+
+/// Represents a method executed by [`FakeApi`].
+pub struct ExecutedMethod {
+    method: Box<dyn Any + Send>,
+    type_name: &'static str,
+    into_payload: fn(Box<dyn Any + Send>) -> Payload,
+}
+
+impl ExecutedMethod {
+    fn new<M>(method: M) -> Self
+    where
+        M: Method + Send + 'static,
+    {
+        Self {
+            method: Box::new(method),
+            type_name: type_name::<M>(),
+            into_payload: |method| method.downcast::<M>().unwrap().into_payload(),
+        }
+    }
+
+    /// Returns whether the method is of type `M`.
+    pub fn is<M>(&self) -> bool
+    where
+        M: 'static,
+    {
+        self.method.is::<M>()
+    }
+
+    /// Returns a reference to the method if it is of type `M`.
+    pub fn downcast_ref<M>(&self) -> Option<&M>
+    where
+        M: 'static,
+    {
+        self.method.downcast_ref()
+    }
+
+    /// Returns the name of the method type.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Converts the method into a payload.
+    pub fn into_payload(self) -> Payload {
+        (self.into_payload)(self.method)
+    }
+}
+
+impl fmt::Debug for ExecutedMethod {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_struct("ExecutedMethod")
+            .field("type_name", &self.type_name)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::{Close, LogOut};
+
+    #[tokio::test]
+    async fn fake_api() {
+        let api = FakeApi::default();
+        api.push_response::<Close>(true);
+        api.push_error::<Close>(ResponseError::new("Too Many Requests").with_retry_after(1));
+
+        assert!(api.execute(Close).await.unwrap());
+        let err = api.execute(Close).await.unwrap_err();
+        assert!(matches!(err, ExecuteError::Response(ref err) if err.retry_after() == Some(1)));
+
+        let mut executed = api.take_executed();
+        assert_eq!(executed.len(), 2);
+        assert!(executed[0].is::<Close>());
+        assert!(!executed[0].is::<LogOut>());
+        assert!(executed[0].downcast_ref::<Close>().is_some());
+        assert!(executed[0].downcast_ref::<LogOut>().is_none());
+        assert!(executed[0].type_name().ends_with("::Close"));
+        assert_eq!(executed.remove(1).into_payload().method_name(), "close");
+        assert!(api.take_executed().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fake_api_raw_method() {
+        let api = FakeApi::default();
+        api.push_raw_response("getMe", json!({"id": 1}));
+        api.push_raw_error("close", ResponseError::new("Bad Request"));
+
+        let err = api.execute(RawMethod::new("close")).await.unwrap_err();
+        assert!(err.to_string().ends_with("Bad Request"));
+        let value = api.execute(RawMethod::new("getMe")).await.unwrap();
+        assert_eq!(value, json!({"id": 1}));
+
+        let executed = api.take_executed();
+        assert_eq!(executed[1].downcast_ref::<RawMethod>().unwrap().name(), "getMe");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "No response for logOut")]
+    async fn fake_api_no_response() {
+        let api = FakeApi::default();
+        api.push_response::<Close>(true);
+        let _ = api.execute(LogOut).await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "No response for logOut")]
+    async fn fake_api_raw_method_no_response() {
+        let api = FakeApi::default();
+        api.push_raw_response("close", json!(true));
+        let _ = api.execute(RawMethod::new("logOut")).await;
+    }
+}
@@ -1,21 +1,44 @@
-use std::{collections::HashMap, error::Error, fmt, fmt::Formatter};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    fmt::Formatter,
+    io::{Error as IoError, ErrorKind as IoErrorKind},
+    path::{Path, PathBuf},
+};
 
+use futures_util::stream::{self, Stream, TryStreamExt};
 use mime::Mime;
 use reqwest::{
     Body,
     Error as ReqwestError,
+    Url,
     multipart::{Form as MultipartForm, Part},
 };
-use tokio::io::AsyncRead;
-use tokio_util::codec::{BytesCodec, FramedRead};
+use serde_json::Value as JsonValue;
+use tokio::{fs::File, io::AsyncRead};
+use tokio_util::{
+    codec::{BytesCodec, FramedRead},
+    io::ReaderStream,
+};
+
+use crate::types::{UploadProgress, UploadProgressHandler};
 
 pub(crate) enum FormValue {
     Text(String),
     File {
         name: Option<String>,
+        size: Option<u64>,
         mime_type: Option<Mime>,
+        progress_handler: Option<UploadProgressHandler>,
         reader: FramedRead<Box<dyn AsyncRead + Send + Sync + Unpin>, BytesCodec>,
     },
+    Path {
+        name: Option<String>,
+        mime_type: Option<Mime>,
+        path: PathBuf,
+        progress_handler: Option<UploadProgressHandler>,
+    },
 }
 
 impl fmt::Debug for FormValue {
@@ -27,6 +50,14 @@ impl fmt::Debug for FormValue {
                 .field("name", name)
                 .field("mime_type", mime_type)
                 .finish(),
+            Self::Path {
+                name, mime_type, path, ..
+            } => out
+                .debug_struct("FormValue::Path")
+                .field("name", name)
+                .field("mime_type", mime_type)
+                .field("path", path)
+                .finish(),
         }
     }
 }
@@ -47,11 +78,45 @@ impl PartialEq for FormValue {
                     ..
                 },
             ) => a_name.eq(b_name) && a_mime_type.eq(b_mime_type),
+            (
+                Self::Path {
+                    name: a_name,
+                    mime_type: a_mime_type,
+                    path: a_path,
+                    ..
+                },
+                Self::Path {
+                    name: b_name,
+                    mime_type: b_mime_type,
+                    path: b_path,
+                    ..
+                },
+            ) => a_name.eq(b_name) && a_mime_type.eq(b_mime_type) && a_path.eq(b_path),
             _ => false,
         }
     }
 }
 
+impl FormValue {
+    fn try_clone(&self) -> Option<Self> {
+        match self {
+            Self::Text(value) => Some(Self::Text(value.clone())),
+            Self::File { .. } => None,
+            Self::Path {
+                name,
+                mime_type,
+                path,
+                progress_handler,
+            } => Some(Self::Path {
+                name: name.clone(),
+                mime_type: mime_type.clone(),
+                path: path.clone(),
+                progress_handler: progress_handler.clone(),
+            }),
+        }
+    }
+}
+
 impl<T> From<T> for FormValue
 where
     T: ToString,
@@ -68,24 +133,74 @@ impl TryFrom<FormValue> for Part {
         Ok(match value {
             FormValue::Text(text) => Part::text(text),
             FormValue::File {
+                name,
+                size,
+                mime_type,
+                progress_handler,
                 reader,
+            } => {
+                let reader = track_upload_progress(reader, size, progress_handler);
+                create_file_part(Body::wrap_stream(reader), name, mime_type)?
+            }
+            FormValue::Path {
                 name,
                 mime_type,
+                path,
+                progress_handler,
             } => {
-                let body = Body::wrap_stream(reader);
-                let mut part = Part::stream(body);
-                if let Some(name) = name {
-                    part = part.file_name(name);
-                }
-                if let Some(mime_type) = mime_type {
-                    part = part.mime_str(mime_type.as_ref()).map_err(FormError::Mime)?;
-                }
-                part
+                let reader = stream::once(async move {
+                    let file = File::open(path).await?;
+                    let size = file.metadata().await?.len();
+                    Ok::<_, IoError>((file, size))
+                })
+                .map_ok(move |(file, size)| {
+                    track_upload_progress(ReaderStream::new(file), Some(size), progress_handler.clone())
+                })
+                .try_flatten();
+                create_file_part(Body::wrap_stream(reader), name, mime_type)?
             }
         })
     }
 }
 
+fn track_upload_progress<S, T, E>(
+    stream: S,
+    total: Option<u64>,
+    progress_handler: Option<UploadProgressHandler>,
+) -> impl Stream<Item = Result<T, E>>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: AsRef<[u8]>,
+{
+    let mut sent = 0;
+    stream.inspect_ok(move |chunk| {
+        if let Some(progress_handler) = &progress_handler {
+            sent += chunk.as_ref().len() as u64;
+            progress_handler(UploadProgress::new(sent, total));
+        }
+    })
+}
+
+fn get_file_uri(path: &Path) -> Result<Url, IoError> {
+    Url::from_file_path(std::path::absolute(path)?).map_err(|()| {
+        IoError::new(
+            IoErrorKind::InvalidInput,
+            format!("can not convert {} to a URI", path.display()),
+        )
+    })
+}
+
+fn create_file_part(body: Body, name: Option<String>, mime_type: Option<Mime>) -> Result<Part, FormError> {
+    let mut part = Part::stream(body);
+    if let Some(name) = name {
+        part = part.file_name(name);
+    }
+    if let Some(mime_type) = mime_type {
+        part = part.mime_str(mime_type.as_ref()).map_err(FormError::Mime)?;
+    }
+    Ok(part)
+}
+
 #[derive(Debug, Default, PartialEq)]
 pub(crate) struct Form {
     fields: HashMap<String, FormValue>,
@@ -104,6 +219,65 @@ impl Form {
         self.fields.insert(name.into(), value.into());
     }
 
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&String, &FormValue)> {
+        self.fields.iter()
+    }
+
+    pub(crate) fn take_field(&mut self, name: &str) -> Option<FormValue> {
+        self.fields.remove(name)
+    }
+
+    pub(crate) fn try_clone(&self) -> Option<Self> {
+        let mut fields = HashMap::with_capacity(self.fields.len());
+        for (name, value) in &self.fields {
+            fields.insert(name.clone(), value.try_clone()?);
+        }
+        Some(Self { fields })
+    }
+
+    /// Replaces files on a filesystem with `file://` URIs.
+    ///
+    /// A file referenced from other fields using `attach://<name>`, e.g. in a media group,
+    /// is removed and the reference is replaced with the URI.
+    ///
+    /// Used to upload files by a local Bot API server.
+    pub(crate) fn use_file_uris(&mut self) -> Result<(), IoError> {
+        let paths: Vec<(String, PathBuf)> = self
+            .fields
+            .iter()
+            .filter_map(|(name, value)| match value {
+                FormValue::Path { path, .. } => Some((name.clone(), path.clone())),
+                _ => None,
+            })
+            .collect();
+        for (name, path) in paths {
+            let uri = get_file_uri(&path)?;
+            let reference = JsonValue::from(format!("attach://{name}")).to_string();
+            let mut is_referenced = false;
+            for value in self.fields.values_mut() {
+                if let FormValue::Text(text) = value
+                    && text.contains(&reference)
+                {
+                    *text = text.replace(&reference, &JsonValue::from(uri.as_str()).to_string());
+                    is_referenced = true;
+                }
+            }
+            if is_referenced {
+                self.fields.remove(&name);
+            } else {
+                self.fields.insert(name, FormValue::Text(uri.into()));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn get_text(&self, name: &str) -> Option<&str> {
+        match self.fields.get(name) {
+            Some(FormValue::Text(value)) => Some(value),
+            _ => None,
+        }
+    }
+
     pub(crate) fn remove_field<N>(&mut self, name: N)
     where
         N: Into<String>,
@@ -159,6 +333,8 @@ impl TryFrom<Form> for MultipartForm {
 /// An error occurred when building multipart form
 #[derive(Debug)]
 pub enum FormError {
+    /// Failed to resolve a path of a file
+    Io(IoError),
     /// Failed to set MIME type
     Mime(ReqwestError),
 }
@@ -166,6 +342,7 @@ pub enum FormError {
 impl Error for FormError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         Some(match self {
+            FormError::Io(err) => err,
             FormError::Mime(err) => err,
         })
     }
@@ -174,6 +351,7 @@ impl Error for FormError {
 impl fmt::Display for FormError {
     fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            FormError::Io(err) => write!(out, "can not resolve a path of a file: {err}"),
             FormError::Mime(err) => write!(out, "can not set MIME type: {err}"),
         }
     }
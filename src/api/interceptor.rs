@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use futures_util::future::{BoxFuture, FutureExt};
+use serde_json::{Value as JsonValue, value::to_raw_value};
+
+use super::{
+    client::{Client, ExecuteError, ReceivedResponse},
+    payload::Payload,
+    token::BotToken,
+};
+use crate::types::Response;
+
+/// Represents an interceptor around [`Client::execute`].
+///
+/// An interceptor receives the payload of each executed method and the rest of the chain.
+/// It can inspect or modify the payload, call [`Next::run`] to proceed,
+/// inspect or replace the response, or return a response without sending a request at all.
+///
+/// The result of a method is passed through the chain as [`JsonValue`]
+/// and converted to [`crate::api::Method::Response`] at the end.
+///
+/// # Example
+///
+/// ```
+/// use futures_util::future::BoxFuture;
+/// use serde_json::{Value as JsonValue, value::to_raw_value};
+/// use tgbot::{
+///     api::{Client, ExecuteError, Interceptor, Next, Payload},
+///     types::Response,
+/// };
+///
+/// struct Logger;
+///
+/// impl Interceptor for Logger {
+///     fn intercept<'a>(
+///         &'a self,
+///         payload: Payload,
+///         next: Next<'a>,
+///     ) -> BoxFuture<'a, Result<Response<JsonValue>, ExecuteError>> {
+///         Box::pin(async move {
+///             let method_name = payload.method_name().to_string();
+///             let result = next.run(payload).await;
+///             println!("{method_name}: {}", result.is_ok());
+///             result
+///         })
+///     }
+/// }
+///
+/// let client = Client::new("token").unwrap().with_interceptor(Logger);
+/// ```
+pub trait Interceptor: Send + Sync {
+    /// Intercepts a method execution.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload of the method.
+    /// * `next` - The rest of the chain.
+    fn intercept<'a>(
+        &'a self,
+        payload: Payload,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response<JsonValue>, ExecuteError>>;
+}
+
+/// Represents the rest of an interceptor chain.
+///
+/// Clone the chain to pass a payload more than once, e.g. to send it again after an error.
+#[derive(Clone)]
+pub struct Next<'a> {
+    client: &'a Client,
+    interceptors: &'a [Arc<dyn Interceptor>],
+    max_retries: u8,
+}
+
+impl<'a> Next<'a> {
+    pub(super) fn new(client: &'a Client, interceptors: &'a [Arc<dyn Interceptor>], max_retries: u8) -> Self {
+        Self {
+            client,
+            interceptors,
+            max_retries,
+        }
+    }
+
+    /// Returns the token of the bot executing the method.
+    pub(super) fn token(&self) -> BotToken {
+        self.client.token()
+    }
+
+    /// Passes a payload to the next interceptor,
+    /// or sends it to the Telegram Bot API when the chain is over.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload to pass.
+    pub fn run(self, payload: Payload) -> BoxFuture<'a, Result<Response<JsonValue>, ExecuteError>> {
+        match self.interceptors.split_first() {
+            Some((interceptor, interceptors)) => {
+                interceptor.intercept(payload, Next::new(self.client, interceptors, self.max_retries))
+            }
+            None => Box::pin(
+                self.client
+                    .send_payload(payload, self.max_retries)
+                    .map(|result| result.map(|received| received.response)),
+            ),
+        }
+    }
+
+    /// Same as [`Self::run`], but also returns the body of the response as received from the server.
+    ///
+    /// When the chain is not over, the body is serialized from the response
+    /// returned by the next interceptor.
+    pub(super) fn run_raw(self, payload: Payload) -> BoxFuture<'a, Result<ReceivedResponse, ExecuteError>> {
+        if self.interceptors.is_empty() {
+            return Box::pin(self.client.send_payload(payload, self.max_retries));
+        }
+        Box::pin(async move {
+            let response = self.run(payload).await?;
+            let body = to_raw_value(&response)?;
+            Ok(ReceivedResponse { response, body })
+        })
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::time::{Instant, sleep};
+
+use super::payload::Payload;
+use crate::types::{ChatId, Integer};
+
+const DEFAULT_GLOBAL_LIMIT: usize = 30;
+const DEFAULT_GLOBAL_PERIOD: Duration = Duration::from_secs(1);
+const DEFAULT_CHAT_LIMIT: usize = 1;
+const DEFAULT_CHAT_PERIOD: Duration = Duration::from_secs(1);
+const DEFAULT_GROUP_LIMIT: usize = 20;
+const DEFAULT_GROUP_PERIOD: Duration = Duration::from_secs(60);
+
+const SENDING_METHOD_PREFIXES: [&str; 3] = ["send", "forward", "copy"];
+const NOT_SENDING_METHODS: [&str; 4] = [
+    "sendChatAction",
+    "sendChatJoinRequestWebApp",
+    "sendMessageDraft",
+    "sendRichMessageDraft",
+];
+
+/// Limits the rate of outgoing messages according to the Telegram Bot API limits.
+///
+/// The limiter maintains three budgets:
+///
+/// * a global budget shared by all chats; default - 30 messages per second;
+/// * a per-chat budget; default - 1 message per second;
+/// * a per-group budget for groups and channels; default - 20 messages per minute.
+///
+/// A sending method (`send*`, `forward*`, `copy*`) waits until a slot is available
+/// in every budget that applies to its `chat_id`.
+/// Methods which do not send messages, such as `sendChatAction` or `sendMessageDraft`, are not limited.
+///
+/// A chat is considered a group or a channel when its identifier is negative
+/// or when it is a username, since usernames can only be used for public groups and channels.
+/// Clones of a limiter share the same state.
+#[derive(Clone)]
+pub struct RateLimiter {
+    global: Quota,
+    chat: Quota,
+    group: Quota,
+    state: Arc<Mutex<RateLimiterState>>,
+}
+
+impl RateLimiter {
+    /// Sets a new global limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Maximum number of messages; at least 1.
+    /// * `period` - Period of time the limit applies to.
+    pub fn with_global_limit(mut self, count: usize, period: Duration) -> Self {
+        self.global = Quota::new(count, period);
+        self
+    }
+
+    /// Sets a new limit for a single chat.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Maximum number of messages; at least 1.
+    /// * `period` - Period of time the limit applies to.
+    pub fn with_chat_limit(mut self, count: usize, period: Duration) -> Self {
+        self.chat = Quota::new(count, period);
+        self
+    }
+
+    /// Sets a new limit for a single group or channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Maximum number of messages; at least 1.
+    /// * `period` - Period of time the limit applies to.
+    pub fn with_group_limit(mut self, count: usize, period: Duration) -> Self {
+        self.group = Quota::new(count, period);
+        self
+    }
+
+    /// Returns a limiter with the same limits and a separate state.
+    pub(super) fn with_new_state(&self) -> Self {
+        Self {
+            state: Default::default(),
+            ..*self
+        }
+    }
+
+    /// Waits until a message can be sent to the given chat and takes a slot.
+    ///
+    /// # Arguments
+    ///
+    /// * `chat_id` - Identifier of the target chat.
+    pub async fn acquire(&self, chat_id: &ChatId) {
+        loop {
+            let delay = {
+                let mut state = self.state.lock().unwrap();
+                let state = &mut *state;
+                let now = Instant::now();
+                state.cleanup(now, self.global.period.max(self.chat.period).max(self.group.period));
+                let is_group = is_group(chat_id);
+                let mut delay = state.global.delay(self.global, now);
+                delay = delay.max(state.chats.entry(chat_id.clone()).or_default().delay(self.chat, now));
+                if is_group {
+                    delay = delay.max(state.groups.entry(chat_id.clone()).or_default().delay(self.group, now));
+                }
+                if delay.is_zero() {
+                    state.global.push(now);
+                    state.chats.entry(chat_id.clone()).or_default().push(now);
+                    if is_group {
+                        state.groups.entry(chat_id.clone()).or_default().push(now);
+                    }
+                    return;
+                }
+                delay
+            };
+            sleep(delay).await;
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self {
+            global: Quota::new(DEFAULT_GLOBAL_LIMIT, DEFAULT_GLOBAL_PERIOD),
+            chat: Quota::new(DEFAULT_CHAT_LIMIT, DEFAULT_CHAT_PERIOD),
+            group: Quota::new(DEFAULT_GROUP_LIMIT, DEFAULT_GROUP_PERIOD),
+            state: Default::default(),
+        }
+    }
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_struct("RateLimiter")
+            .field("global", &self.global)
+            .field("chat", &self.chat)
+            .field("group", &self.group)
+            .finish()
+    }
+}
+
+/// Returns a chat the payload is rate limited by.
+///
+/// Only sending methods are limited.
+pub(super) fn get_rate_limited_chat_id(payload: &Payload) -> Option<ChatId> {
+    let method_name = payload.method_name();
+    if SENDING_METHOD_PREFIXES
+        .iter()
+        .any(|prefix| method_name.starts_with(prefix))
+        && !NOT_SENDING_METHODS.contains(&method_name)
+    {
+        payload.chat_id()
+    } else {
+        None
+    }
+}
+
+/// Returns whether the chat is a group or a channel.
+///
+/// A username in `chat_id` may only refer to a public group or channel.
+fn is_group(chat_id: &ChatId) -> bool {
+    match chat_id {
+        ChatId::Id(id) => Integer::from(*id) < 0,
+        ChatId::Username(_) => true,
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Quota {
+    count: usize,
+    period: Duration,
+}
+
+impl Quota {
+    fn new(count: usize, period: Duration) -> Self {
+        Self {
+            count: count.max(1),
+            period,
+        }
+    }
+}
+
+#[derive(Default)]
+struct RateLimiterState {
+    global: Window,
+    chats: HashMap<ChatId, Window>,
+    groups: HashMap<ChatId, Window>,
+    last_cleanup: Option<Instant>,
+}
+
+impl RateLimiterState {
+    fn cleanup(&mut self, now: Instant, period: Duration) {
+        match self.last_cleanup {
+            Some(last_cleanup) if now.duration_since(last_cleanup) < period => {}
+            _ => {
+                self.chats.retain(|_, window| window.is_active(now, period));
+                self.groups.retain(|_, window| window.is_active(now, period));
+                self.last_cleanup = Some(now);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Window(VecDeque<Instant>);
+
+impl Window {
+    fn delay(&mut self, quota: Quota, now: Instant) -> Duration {
+        while let Some(first) = self.0.front() {
+            if now.duration_since(*first) >= quota.period {
+                self.0.pop_front();
+            } else {
+                break;
+            }
+        }
+        if self.0.len() < quota.count {
+            Duration::ZERO
+        } else {
+            (self.0[self.0.len() - quota.count] + quota.period).duration_since(now)
+        }
+    }
+
+    fn is_active(&self, now: Instant, period: Duration) -> bool {
+        self.0.back().is_some_and(|last| now.duration_since(*last) < period)
+    }
+
+    fn push(&mut self, now: Instant) {
+        self.0.push_back(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_limiter() {
+        let limiter = RateLimiter::default()
+            .with_global_limit(3, Duration::from_secs(1))
+            .with_chat_limit(2, Duration::from_secs(1))
+            .with_group_limit(2, Duration::from_secs(60));
+
+        let start = Instant::now();
+        limiter.acquire(&ChatId::from(1)).await;
+        limiter.acquire(&ChatId::from(1)).await;
+        limiter.acquire(&ChatId::from(2)).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        limiter.acquire(&ChatId::from(2)).await;
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+
+        let start = Instant::now();
+        limiter.acquire(&ChatId::from(-1)).await;
+        limiter.acquire(&ChatId::from(-1)).await;
+        limiter.acquire(&ChatId::from(-1)).await;
+        assert_eq!(start.elapsed(), Duration::from_secs(60));
+
+        let start = Instant::now();
+        limiter.acquire(&ChatId::from(1)).await;
+        limiter.acquire(&ChatId::from(1)).await;
+        let limiter = limiter.with_new_state();
+        limiter.acquire(&ChatId::from(1)).await;
+        limiter.acquire(&ChatId::from(1)).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn rate_limited_chat_id() {
+        use crate::{api::Method, types::*};
+
+        let payload = SendMessage::new(1, "text").into_payload();
+        assert_eq!(get_rate_limited_chat_id(&payload), Some(ChatId::from(1)));
+
+        let payload = SendMessage::new("@username", "text").into_payload();
+        assert_eq!(get_rate_limited_chat_id(&payload), Some(ChatId::from("@username")));
+
+        let payload = SendDocument::new(-1, InputFile::file_id("file-id")).into_payload();
+        assert_eq!(get_rate_limited_chat_id(&payload), Some(ChatId::from(-1)));
+
+        let payload = GetChat::new(1).into_payload();
+        assert_eq!(get_rate_limited_chat_id(&payload), None);
+
+        let payload = SendChatAction::new(1, ChatAction::Typing).into_payload();
+        assert_eq!(get_rate_limited_chat_id(&payload), None);
+    }
+}
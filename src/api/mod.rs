@@ -1,5 +1,37 @@
-pub use self::{client::*, form::*, payload::*};
+pub use self::{
+    builder::*,
+    cache::*,
+    cassette::*,
+    client::*,
+    download::*,
+    fake::*,
+    form::*,
+    interceptor::*,
+    limiter::*,
+    observer::*,
+    options::*,
+    payload::*,
+    pool::*,
+    raw::*,
+    retry::*,
+    token::*,
+};
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod builder;
+mod cache;
+mod cassette;
 mod client;
+mod download;
+mod fake;
 mod form;
+mod interceptor;
+mod limiter;
+mod observer;
+mod options;
 mod payload;
+mod pool;
+mod raw;
+mod retry;
+mod token;
@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use super::client::ExecuteError;
+
+/// Represents an observer of method executions.
+///
+/// Use it to collect metrics, e.g. latency and error counters by method.
+/// All methods do nothing by default.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use tgbot::api::{Client, ClientObserver, ExecuteError};
+///
+/// struct Metrics;
+///
+/// impl ClientObserver for Metrics {
+///     fn on_request_complete(&self, method_name: &str, duration: Duration, error: Option<&ExecuteError>) {
+///         let kind = error.map(|err| format!("{:?}", err.response_error_kind()));
+///         println!("{method_name}: {duration:?} {kind:?}");
+///     }
+/// }
+///
+/// let client = Client::new("token").unwrap().with_observer(Metrics);
+/// ```
+pub trait ClientObserver: Send + Sync {
+    /// Called when a method execution is started.
+    ///
+    /// # Arguments
+    ///
+    /// * `method_name` - The name of the method.
+    fn on_request_start(&self, method_name: &str) {
+        let _ = method_name;
+    }
+
+    /// Called when a request is going to be retried due to a transport or server error.
+    ///
+    /// # Arguments
+    ///
+    /// * `method_name` - The name of the method.
+    /// * `attempt` - The number of the retry attempt starting from 1.
+    /// * `delay` - The delay before the next attempt.
+    /// * `error` - The error of the previous attempt.
+    fn on_retry(&self, method_name: &str, attempt: u32, delay: Duration, error: &ExecuteError) {
+        let _ = (method_name, attempt, delay, error);
+    }
+
+    /// Called when a request is going to be retried due to flood control.
+    ///
+    /// # Arguments
+    ///
+    /// * `method_name` - The name of the method.
+    /// * `retry_after` - The delay before the next attempt.
+    fn on_flood_wait(&self, method_name: &str, retry_after: Duration) {
+        let _ = (method_name, retry_after);
+    }
+
+    /// Called when a method execution is completed.
+    ///
+    /// # Arguments
+    ///
+    /// * `method_name` - The name of the method.
+    /// * `duration` - The duration of the execution including retries.
+    /// * `error` - The error of the execution, if any.
+    fn on_request_complete(&self, method_name: &str, duration: Duration, error: Option<&ExecuteError>) {
+        let _ = (method_name, duration, error);
+    }
+}
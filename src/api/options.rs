@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+/// A token used to cancel executions, re-exported from `tokio-util`
+/// to avoid depending on a matching version of the crate.
+pub use tokio_util::sync::CancellationToken;
+
+/// Represents options of a method execution.
+///
+/// Used by [`crate::api::Client::execute_with`].
+///
+/// # Example
+///
+/// ```
+/// # async fn execute_with() {
+/// use std::time::Duration;
+///
+/// use tgbot::{
+///     api::{CancellationToken, Client, ExecuteOptions},
+///     types::SendMessage,
+/// };
+///
+/// let client = Client::new("token").unwrap();
+/// let token = CancellationToken::new();
+/// let options = ExecuteOptions::default()
+///     .with_timeout(Duration::from_secs(10))
+///     .with_cancellation_token(token.clone())
+///     .with_max_retries(0);
+/// client.execute_with(SendMessage::new(1, "text"), options).await.unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ExecuteOptions {
+    deadline: Option<Instant>,
+    cancellation_token: Option<CancellationToken>,
+    max_retries: Option<u8>,
+}
+
+impl ExecuteOptions {
+    /// Sets a new deadline of the execution.
+    ///
+    /// When the deadline is reached, the request and retry sleeps are aborted
+    /// and [`crate::api::ExecuteError::Timeout`] is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The deadline; default - none.
+    pub fn with_deadline<T>(mut self, value: T) -> Self
+    where
+        T: Into<Instant>,
+    {
+        self.deadline = Some(value.into());
+        self
+    }
+
+    /// Sets a new deadline of the execution relative to the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The maximum duration of the execution including retries.
+    pub fn with_timeout(self, value: Duration) -> Self {
+        self.with_deadline(Instant::now() + value)
+    }
+
+    /// Returns the deadline of the execution.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Sets a new cancellation token.
+    ///
+    /// When the token is cancelled, the request and retry sleeps are aborted
+    /// and [`crate::api::ExecuteError::Cancelled`] is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The token; default - none.
+    pub fn with_cancellation_token(mut self, value: CancellationToken) -> Self {
+        self.cancellation_token = Some(value);
+        self
+    }
+
+    /// Returns the cancellation token.
+    pub fn cancellation_token(&self) -> Option<&CancellationToken> {
+        self.cancellation_token.as_ref()
+    }
+
+    /// Overrides the number of max retries of the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The number of max retries; see [`crate::api::Client::with_max_retries`].
+    pub fn with_max_retries(mut self, value: u8) -> Self {
+        self.max_retries = Some(value);
+        self
+    }
+
+    /// Returns the overridden number of max retries.
+    pub fn max_retries(&self) -> Option<u8> {
+        self.max_retries
+    }
+}
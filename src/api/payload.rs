@@ -2,19 +2,20 @@ use std::{error::Error, fmt};
 
 use log::debug;
 use reqwest::{Client as HttpClient, Method as HttpMethod, RequestBuilder as HttpRequestBuilder};
-use serde::ser::Serialize;
-use serde_json::Error as JsonError;
+use serde::{de::Error as _, ser::Serialize};
+use serde_json::{Error as JsonError, Value as JsonValue};
 
-use super::form::{Form, FormError};
+use super::form::{Form, FormError, FormValue};
+use crate::types::{ChatId, Integer};
 
 #[derive(Debug)]
 pub(crate) enum PayloadData {
     Form(Form),
-    Json(Result<String, JsonError>),
+    Json(Result<JsonValue, JsonError>),
     Empty,
 }
 
-#[doc(hidden)]
+/// Represents an HTTP request payload of a method.
 #[derive(Debug)]
 pub struct Payload {
     pub(crate) http_method: HttpMethod,
@@ -34,7 +35,7 @@ impl Payload {
     pub(crate) fn json<P: Into<String>>(path: P, data: impl Serialize) -> Self {
         Self {
             http_method: HttpMethod::POST,
-            payload_data: PayloadData::Json(serde_json::to_string(&data)),
+            payload_data: PayloadData::Json(serde_json::to_value(&data)),
             url_path: path.into(),
         }
     }
@@ -47,6 +48,149 @@ impl Payload {
         }
     }
 
+    /// Returns a name of the method.
+    pub fn method_name(&self) -> &str {
+        &self.url_path
+    }
+
+    /// Returns a value of the field.
+    ///
+    /// Values of multipart form fields are returned as strings, file fields are not returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the field.
+    pub fn get_field(&self, name: &str) -> Option<JsonValue> {
+        match &self.payload_data {
+            PayloadData::Form(form) => form.get_text(name).map(|value| JsonValue::String(value.to_string())),
+            PayloadData::Json(Ok(data)) => data.get(name).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Sets a value of the field.
+    ///
+    /// Non-string values are serialized to JSON when the payload is a multipart form.
+    /// An empty payload is turned into a JSON one.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the field.
+    /// * `value` - The value of the field.
+    pub fn set_field<N, V>(&mut self, name: N, value: V) -> Result<(), PayloadError>
+    where
+        N: Into<String>,
+        V: Serialize,
+    {
+        let value = serde_json::to_value(value)?;
+        self.insert_value(name, value);
+        Ok(())
+    }
+
+    pub(crate) fn insert_value<N>(&mut self, name: N, value: JsonValue)
+    where
+        N: Into<String>,
+    {
+        if let PayloadData::Empty = self.payload_data {
+            self.http_method = HttpMethod::POST;
+            self.payload_data = PayloadData::Json(Ok(JsonValue::Object(Default::default())));
+        }
+        match &mut self.payload_data {
+            PayloadData::Form(form) => match value {
+                JsonValue::String(value) => form.insert_field(name, value),
+                value => form.insert_field(name, value),
+            },
+            PayloadData::Json(Ok(JsonValue::Object(data))) => {
+                data.insert(name.into(), value);
+            }
+            PayloadData::Json(data @ Ok(_)) => *data = Err(get_not_object_error()),
+            _ => {}
+        }
+    }
+
+    pub(crate) fn insert_form_value<N>(&mut self, name: N, value: FormValue)
+    where
+        N: Into<String>,
+    {
+        let payload_data = std::mem::replace(&mut self.payload_data, PayloadData::Empty);
+        let mut form = match payload_data {
+            PayloadData::Form(form) => form,
+            PayloadData::Json(Ok(JsonValue::Object(data))) => {
+                let mut form = Form::default();
+                for (name, value) in data {
+                    match value {
+                        JsonValue::String(value) => form.insert_field(name, value),
+                        value => form.insert_field(name, value),
+                    }
+                }
+                form
+            }
+            PayloadData::Empty => Form::default(),
+            PayloadData::Json(Ok(_)) => {
+                self.payload_data = PayloadData::Json(Err(get_not_object_error()));
+                return;
+            }
+            payload_data @ PayloadData::Json(Err(_)) => {
+                self.payload_data = payload_data;
+                return;
+            }
+        };
+        form.insert_field(name, value);
+        self.http_method = HttpMethod::POST;
+        self.payload_data = PayloadData::Form(form);
+    }
+
+    /// Returns an identifier of the target chat.
+    ///
+    /// Returns `None` when the method has no `chat_id` field.
+    pub fn chat_id(&self) -> Option<ChatId> {
+        match &self.payload_data {
+            PayloadData::Form(form) => form.get_text("chat_id").map(|value| match value.parse::<Integer>() {
+                Ok(id) => ChatId::from(id),
+                Err(_) => ChatId::from(value),
+            }),
+            PayloadData::Json(Ok(data)) => data
+                .get("chat_id")
+                .and_then(|value| serde_json::from_value(value.clone()).ok()),
+            _ => None,
+        }
+    }
+
+    /// Sets a new identifier of the target chat.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The identifier of the chat.
+    pub fn set_chat_id<T>(&mut self, value: T) -> Result<(), PayloadError>
+    where
+        T: Into<ChatId>,
+    {
+        self.set_field("chat_id", value.into())
+    }
+
+    pub(crate) fn use_file_uris(&mut self) -> Result<(), PayloadError> {
+        if let PayloadData::Form(form) = &mut self.payload_data {
+            form.use_file_uris().map_err(FormError::Io)?;
+        }
+        Ok(())
+    }
+
+    /// Returns a copy of the payload.
+    ///
+    /// Returns `None` when the payload contains a stream or an invalid JSON body.
+    pub(crate) fn try_clone(&self) -> Option<Self> {
+        Some(Self {
+            http_method: self.http_method.clone(),
+            payload_data: match &self.payload_data {
+                PayloadData::Form(form) => PayloadData::Form(form.try_clone()?),
+                PayloadData::Json(Ok(data)) => PayloadData::Json(Ok(data.clone())),
+                PayloadData::Json(Err(_)) => return None,
+                PayloadData::Empty => PayloadData::Empty,
+            },
+            url_path: self.url_path.clone(),
+        })
+    }
+
     pub(crate) fn build_url(&self, base_url: &str, token: &str) -> String {
         format!("{}/bot{}/{}", base_url, token, self.url_path)
     }
@@ -66,7 +210,7 @@ impl Payload {
                 builder.multipart(form)
             }
             PayloadData::Json(data) => {
-                let data = data?;
+                let data = serde_json::to_string(&data?)?;
                 debug!("Sending JSON body: {data:?}");
                 builder.header("Content-Type", "application/json").body(data)
             }
@@ -78,6 +222,11 @@ impl Payload {
     }
 }
 
+/// Returns an error for parameters which can not be extended with a field.
+fn get_not_object_error() -> JsonError {
+    JsonError::custom("parameters must be a JSON object to add a field")
+}
+
 /// An error when building an HTTP request
 #[derive(Debug, derive_more::From)]
 pub enum PayloadError {
@@ -0,0 +1,216 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+use futures_util::future::BoxFuture;
+use serde_json::Value as JsonValue;
+
+use super::{
+    client::{Client, ExecuteError},
+    interceptor::{Interceptor, Next},
+    payload::Payload,
+    token::BotToken,
+};
+use crate::types::{GetManagedBotToken, Integer, ReplaceManagedBotToken, Response, UserPeerId};
+
+type Clients = Arc<RwLock<HashMap<UserPeerId, Client>>>;
+
+/// Represents a pool of clients for managed bots.
+///
+/// All clients share the HTTP connection pool and settings of the manager bot client,
+/// but each managed bot gets a separate state of the rate limiter,
+/// since the Telegram Bot API limits are applied per bot.
+///
+/// When the manager bot executes [`GetManagedBotToken`] or [`ReplaceManagedBotToken`]
+/// using [`Self::manager`], the client of the managed bot is updated with the received token;
+/// clients obtained before the token is replaced use the new token as well.
+///
+/// Clones of a pool share the same state.
+///
+/// # Example
+///
+/// ```
+/// # async fn pool() {
+/// use tgbot::{api::{Client, ClientPool}, types::GetBot};
+///
+/// let pool = ClientPool::new(Client::new("manager-token").unwrap());
+/// let client = pool.get_or_fetch(1).await.unwrap();
+/// let bot = client.execute(GetBot).await.unwrap();
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ClientPool {
+    base: Client,
+    manager: Client,
+    clients: Clients,
+}
+
+impl ClientPool {
+    /// Creates a new `ClientPool`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client of the manager bot.
+    pub fn new(client: Client) -> Self {
+        let clients = Clients::default();
+        let manager = client.clone().with_interceptor(TokenRotation {
+            base: client.clone(),
+            clients: clients.clone(),
+        });
+        Self {
+            base: client,
+            manager,
+            clients,
+        }
+    }
+
+    /// Returns the client of the manager bot.
+    pub fn manager(&self) -> &Client {
+        &self.manager
+    }
+
+    /// Returns a client of a managed bot.
+    ///
+    /// # Arguments
+    ///
+    /// * `bot_id` - Identifier of the managed bot.
+    pub fn get<T>(&self, bot_id: T) -> Option<Client>
+    where
+        T: Into<UserPeerId>,
+    {
+        self.clients.read().unwrap().get(&bot_id.into()).cloned()
+    }
+
+    /// Returns a client of a managed bot;
+    /// the token is obtained using [`GetManagedBotToken`] when the client is missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `bot_id` - Identifier of the managed bot.
+    pub async fn get_or_fetch<T>(&self, bot_id: T) -> Result<Client, ExecuteError>
+    where
+        T: Into<UserPeerId>,
+    {
+        let bot_id = bot_id.into();
+        if let Some(client) = self.get(bot_id) {
+            return Ok(client);
+        }
+        let token = self
+            .manager
+            .execute(GetManagedBotToken::from(Integer::from(bot_id)))
+            .await?;
+        Ok(self.get_or_insert(bot_id, token))
+    }
+
+    /// Adds a client of a managed bot.
+    ///
+    /// When the client already exists, its token is replaced.
+    ///
+    /// Returns the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `bot_id` - Identifier of the managed bot.
+    /// * `token` - Token of the managed bot.
+    pub fn insert<A, B>(&self, bot_id: A, token: B) -> Client
+    where
+        A: Into<UserPeerId>,
+        B: Into<BotToken>,
+    {
+        insert_client(&self.clients, &self.base, bot_id.into(), token.into())
+    }
+
+    /// Removes a client of a managed bot.
+    ///
+    /// # Arguments
+    ///
+    /// * `bot_id` - Identifier of the managed bot.
+    pub fn remove<T>(&self, bot_id: T) -> Option<Client>
+    where
+        T: Into<UserPeerId>,
+    {
+        self.clients.write().unwrap().remove(&bot_id.into())
+    }
+
+    /// Replaces the token of a managed bot using [`ReplaceManagedBotToken`].
+    ///
+    /// Returns a client with the new token.
+    ///
+    /// # Arguments
+    ///
+    /// * `bot_id` - Identifier of the managed bot.
+    pub async fn replace_token<T>(&self, bot_id: T) -> Result<Client, ExecuteError>
+    where
+        T: Into<UserPeerId>,
+    {
+        let bot_id = bot_id.into();
+        let token = self
+            .manager
+            .execute(ReplaceManagedBotToken::from(Integer::from(bot_id)))
+            .await?;
+        Ok(self.get_or_insert(bot_id, token))
+    }
+
+    /// Returns a client updated by [`TokenRotation`],
+    /// or adds it when the response has been returned by an earlier interceptor.
+    fn get_or_insert(&self, bot_id: UserPeerId, token: String) -> Client {
+        match self.get(bot_id) {
+            Some(client) if client.token().expose_secret() == token => client,
+            _ => self.insert(bot_id, token),
+        }
+    }
+}
+
+impl fmt::Debug for ClientPool {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_struct("ClientPool")
+            .field("manager", &self.manager)
+            .field("clients", &self.clients.read().unwrap().len())
+            .finish()
+    }
+}
+
+fn insert_client(clients: &Clients, base: &Client, bot_id: UserPeerId, token: BotToken) -> Client {
+    let mut clients = clients.write().unwrap();
+    match clients.get(&bot_id) {
+        Some(client) => {
+            client.replace_token(token);
+            client.clone()
+        }
+        None => {
+            let client = base.for_bot(token);
+            clients.insert(bot_id, client.clone());
+            client
+        }
+    }
+}
+
+struct TokenRotation {
+    base: Client,
+    clients: Clients,
+}
+
+impl Interceptor for TokenRotation {
+    fn intercept<'a>(
+        &'a self,
+        payload: Payload,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response<JsonValue>, ExecuteError>> {
+        Box::pin(async move {
+            let bot_id = match payload.method_name() {
+                "getManagedBotToken" | "replaceManagedBotToken" => payload
+                    .get_field("user_id")
+                    .and_then(|x| x.as_i64())
+                    .map(UserPeerId::from),
+                _ => None,
+            };
+            let response = next.run(payload).await?;
+            if let (Some(bot_id), Response::Success(JsonValue::String(token))) = (bot_id, &response) {
+                insert_client(&self.clients, &self.base, bot_id, BotToken::from(token.as_str()));
+            }
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,211 @@
+use std::marker::PhantomData;
+
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value as JsonValue;
+
+use super::{
+    client::Method,
+    payload::{Payload, PayloadData},
+};
+use crate::types::InputFile;
+
+/// Represents a method which is not supported by the library yet.
+///
+/// The result of the method is returned as [`JsonValue`] by default;
+/// use [`RawMethod::with_response_type`] to deserialize it into another type.
+///
+/// # Example
+///
+/// ```
+/// # async fn execute_raw_method() {
+/// use serde_json::{Value as JsonValue, json};
+/// use tgbot::api::{Client, RawMethod};
+///
+/// let client = Client::new("token").unwrap();
+/// let method = RawMethod::json("sendMessage", json!({"chat_id": 1, "text": "text"}));
+/// let message: JsonValue = client.execute(method).await.unwrap();
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RawMethod<T = JsonValue> {
+    payload: Payload,
+    response_type: PhantomData<fn() -> T>,
+}
+
+impl RawMethod {
+    /// Creates a new `RawMethod` without parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the method, e.g. `getMe`.
+    pub fn new<T>(name: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::from_payload(Payload::empty(name))
+    }
+
+    /// Creates a new `RawMethod` with parameters sent as a JSON object.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the method.
+    /// * `data` - The parameters of the method; must be serialized into a JSON object.
+    pub fn json<A, B>(name: A, data: B) -> Self
+    where
+        A: Into<String>,
+        B: Serialize,
+    {
+        Self::from_payload(Payload::json(name, data))
+    }
+}
+
+impl<T> RawMethod<T> {
+    fn from_payload(payload: Payload) -> Self {
+        Self {
+            payload,
+            response_type: PhantomData,
+        }
+    }
+
+    /// Sets a new parameter.
+    ///
+    /// Executing the method fails when the parameters are not a JSON object.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the parameter.
+    /// * `value` - The value of the parameter.
+    pub fn with_field<A, B>(mut self, name: A, value: B) -> Self
+    where
+        A: Into<String>,
+        B: Serialize,
+    {
+        match serde_json::to_value(value) {
+            Ok(value) => self.payload.insert_value(name, value),
+            Err(err) => self.payload.payload_data = PayloadData::Json(Err(err)),
+        }
+        self
+    }
+
+    /// Sets a new file parameter.
+    ///
+    /// The parameters are sent using `multipart/form-data` after the file is added.
+    /// Executing the method fails when the parameters are not a JSON object.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the parameter.
+    /// * `value` - The file.
+    pub fn with_file<A, B>(mut self, name: A, value: B) -> Self
+    where
+        A: Into<String>,
+        B: Into<InputFile>,
+    {
+        self.payload.insert_form_value(name, value.into().into());
+        self
+    }
+
+    /// Sets a new type of the result.
+    pub fn with_response_type<R>(self) -> RawMethod<R> {
+        RawMethod::from_payload(self.payload)
+    }
+
+    pub(super) fn name(&self) -> &str {
+        self.payload.method_name()
+    }
+}
+
+impl<T> Method for RawMethod<T>
+where
+    T: DeserializeOwned,
+{
+    type Response = T;
+
+    fn into_payload(self) -> Payload {
+        self.payload
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use reqwest::Method as HttpMethod;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        api::FormValue,
+        types::{InputFileReader, User},
+    };
+
+    #[test]
+    fn raw_method() {
+        let payload = RawMethod::new("getMe").into_payload();
+        assert_eq!(payload.method_name(), "getMe");
+        assert_eq!(payload.http_method, HttpMethod::GET);
+        assert!(matches!(payload.payload_data, PayloadData::Empty));
+
+        let payload = RawMethod::new("getChat").with_field("chat_id", 1).into_payload();
+        assert_eq!(payload.http_method, HttpMethod::POST);
+        assert!(matches!(payload.payload_data, PayloadData::Json(Ok(ref data)) if data == &json!({"chat_id": 1})));
+
+        let payload = RawMethod::json("getMe", json!({}))
+            .with_response_type::<User>()
+            .into_payload();
+        assert!(matches!(payload.payload_data, PayloadData::Json(Ok(ref data)) if data == &json!({})));
+
+        let payload = RawMethod::json("sendDocument", json!({"chat_id": 1}))
+            .with_file(
+                "document",
+                InputFileReader::from(Cursor::new(b"data")).with_file_name("name.txt"),
+            )
+            .with_field("caption", "caption")
+            .with_field("disable_notification", true)
+            .into_payload();
+        assert_eq!(payload.http_method, HttpMethod::POST);
+        match payload.payload_data {
+            PayloadData::Form(form) => assert_eq!(
+                form.into_fields(),
+                vec![
+                    (String::from("caption"), FormValue::Text(String::from("caption"))),
+                    (String::from("chat_id"), FormValue::Text(String::from("1"))),
+                    (
+                        String::from("disable_notification"),
+                        FormValue::Text(String::from("true"))
+                    ),
+                    (
+                        String::from("document"),
+                        InputFile::from(InputFileReader::from(Cursor::new(b"")).with_file_name("name.txt")).into()
+                    ),
+                ]
+            ),
+            payload_data => panic!("Unexpected payload data: {payload_data:?}"),
+        }
+    }
+
+    #[test]
+    fn raw_method_not_object() {
+        let http_client = reqwest::Client::new();
+        let document = || InputFileReader::from(Cursor::new(b"data")).with_file_name("name.txt");
+        for payload in [
+            RawMethod::json("sendDocument", json!([1])).with_field("caption", "caption"),
+            RawMethod::json("sendDocument", json!([1])).with_file("document", document()),
+            RawMethod::json("sendDocument", json!(1))
+                .with_file("document", document())
+                .with_field("caption", "caption"),
+        ]
+        .map(|x| x.into_payload())
+        {
+            assert!(matches!(payload.payload_data, PayloadData::Json(Err(_))));
+            let err = payload
+                .into_http_request_builder(&http_client, "https://example.com", "token")
+                .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "could not build an HTTP request: parameters must be a JSON object to add a field"
+            );
+        }
+    }
+}
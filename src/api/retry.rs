@@ -0,0 +1,281 @@
+use std::{sync::Arc, time::Duration};
+
+use super::client::ExecuteError;
+use crate::types::ResponseErrorKind;
+
+const DEFAULT_INITIAL_INTERVAL: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_INTERVAL: Duration = Duration::from_secs(30);
+const DEFAULT_MAX_ELAPSED_TIME: Duration = Duration::from_secs(120);
+const DEFAULT_MULTIPLIER: f64 = 2.0;
+const DEFAULT_RANDOMIZATION_FACTOR: f64 = 0.5;
+
+const IDEMPOTENT_METHOD_PREFIXES: [&str; 4] = ["get", "set", "edit", "delete"];
+
+/// Represents a policy for retrying requests failed due to transport or server errors.
+pub trait RetryPolicy: Send + Sync {
+    /// Returns a delay before the next attempt or `None` if the request should not be retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `method_name` - A name of the method, e.g. `sendMessage`.
+    /// * `err` - An error of the failed attempt.
+    /// * `attempt` - A number of the failed attempt; starts from 1.
+    /// * `elapsed` - Time elapsed since the first attempt.
+    fn get_delay(&self, method_name: &str, err: &ExecuteError, attempt: u32, elapsed: Duration) -> Option<Duration>;
+}
+
+impl<T> RetryPolicy for Arc<T>
+where
+    T: RetryPolicy + ?Sized,
+{
+    fn get_delay(&self, method_name: &str, err: &ExecuteError, attempt: u32, elapsed: Duration) -> Option<Duration> {
+        T::get_delay(self, method_name, err, attempt, elapsed)
+    }
+}
+
+/// Retries requests with exponentially increasing delays.
+///
+/// The following errors are retried:
+///
+/// * connection errors;
+/// * timeouts and other errors of sending a request;
+/// * HTTP responses with 5xx status codes;
+/// * Telegram errors with 5xx error codes.
+///
+/// A request which has timed out, failed while sending or failed with a server error
+/// may have been executed by the server, so only connection errors are retried for all methods;
+/// other errors are retried only for idempotent methods by default,
+/// i.e. methods with names starting with `get`, `set`, `edit` or `delete`;
+/// use [`Self::with_retry_non_idempotent`] to retry other methods, e.g. `sendMessage`, as well.
+///
+/// A delay for the attempt `n` is `initial_interval * multiplier^(n - 1)`,
+/// limited by `max_interval` and randomized by `randomization_factor`.
+/// The request is not retried once `max_elapsed_time` is exceeded.
+#[derive(Clone, Copy, Debug)]
+pub struct ExponentialBackoff {
+    initial_interval: Duration,
+    max_interval: Duration,
+    max_elapsed_time: Option<Duration>,
+    multiplier: f64,
+    randomization_factor: f64,
+    retry_non_idempotent: bool,
+}
+
+impl ExponentialBackoff {
+    /// Sets a new initial interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A delay before the first retry; default - 500ms.
+    pub fn with_initial_interval(mut self, value: Duration) -> Self {
+        self.initial_interval = value;
+        self
+    }
+
+    /// Sets a new maximum interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Maximum delay between two attempts; default - 30s.
+    pub fn with_max_interval(mut self, value: Duration) -> Self {
+        self.max_interval = value;
+        self
+    }
+
+    /// Sets a new maximum elapsed time.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Time after which the request is not retried anymore;
+    ///   `None` - retry forever; default - 120s.
+    pub fn with_max_elapsed_time(mut self, value: Option<Duration>) -> Self {
+        self.max_elapsed_time = value;
+        self
+    }
+
+    /// Sets a new multiplier.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A factor the delay is multiplied by after each attempt; at least 1; default - 2.
+    pub fn with_multiplier(mut self, value: f64) -> Self {
+        self.multiplier = value.max(1.0);
+        self
+    }
+
+    /// Sets a new randomization factor.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A delay is randomly chosen from the
+    ///   `[delay * (1 - value), delay * (1 + value)]` range; 0—1; default - 0.5.
+    pub fn with_randomization_factor(mut self, value: f64) -> Self {
+        self.randomization_factor = value.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets whether to retry non-idempotent methods on timeouts, errors of sending a request and server errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Whether to retry; default - `false`.
+    pub fn with_retry_non_idempotent(mut self, value: bool) -> Self {
+        self.retry_non_idempotent = value;
+        self
+    }
+
+    fn is_retryable(&self, method_name: &str, err: &ExecuteError) -> bool {
+        let is_idempotent = self.retry_non_idempotent || is_idempotent(method_name);
+        match err {
+            ExecuteError::Http(err) => {
+                err.is_connect()
+                    || (is_idempotent
+                        && (err.status().is_some_and(|status| status.is_server_error())
+                            || err.is_timeout()
+                            || err.is_request()))
+            }
+            ExecuteError::Response(err) => is_idempotent && err.kind() == ResponseErrorKind::ServerError,
+            _ => false,
+        }
+    }
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        Self {
+            initial_interval: DEFAULT_INITIAL_INTERVAL,
+            max_interval: DEFAULT_MAX_INTERVAL,
+            max_elapsed_time: Some(DEFAULT_MAX_ELAPSED_TIME),
+            multiplier: DEFAULT_MULTIPLIER,
+            randomization_factor: DEFAULT_RANDOMIZATION_FACTOR,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn get_delay(&self, method_name: &str, err: &ExecuteError, attempt: u32, elapsed: Duration) -> Option<Duration> {
+        if !self.is_retryable(method_name, err) {
+            return None;
+        }
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        // Durations are computed in seconds to avoid overflows of large intervals
+        let interval =
+            (self.initial_interval.as_secs_f64() * self.multiplier.powi(exponent)).min(self.max_interval.as_secs_f64());
+        let delta = interval * self.randomization_factor;
+        let delay = interval - delta + 2.0 * delta * fastrand::f64();
+        let delay = Duration::try_from_secs_f64(delay).unwrap_or(Duration::MAX);
+        match self.max_elapsed_time {
+            Some(max_elapsed_time) if elapsed.checked_add(delay).is_none_or(|x| x > max_elapsed_time) => None,
+            _ => Some(delay),
+        }
+    }
+}
+
+fn is_idempotent(method_name: &str) -> bool {
+    IDEMPOTENT_METHOD_PREFIXES
+        .iter()
+        .any(|prefix| method_name.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ResponseError;
+
+    #[test]
+    fn exponential_backoff() {
+        let policy = ExponentialBackoff::default()
+            .with_initial_interval(Duration::from_secs(1))
+            .with_max_interval(Duration::from_secs(5))
+            .with_max_elapsed_time(Some(Duration::from_secs(60)))
+            .with_multiplier(2.0)
+            .with_randomization_factor(0.0);
+        let err = ExecuteError::Response(ResponseError::new("Bad Gateway").with_error_code(502));
+        assert_eq!(
+            policy.get_delay("getMe", &err, 1, Duration::ZERO),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            policy.get_delay("getMe", &err, 2, Duration::ZERO),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            policy.get_delay("getMe", &err, 3, Duration::ZERO),
+            Some(Duration::from_secs(4))
+        );
+        assert_eq!(
+            policy.get_delay("getMe", &err, 4, Duration::ZERO),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            policy.get_delay("getMe", &err, 100, Duration::ZERO),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(policy.get_delay("getMe", &err, 4, Duration::from_secs(56)), None);
+
+        let err = ExecuteError::Response(ResponseError::new("Bad Request").with_error_code(400));
+        assert_eq!(policy.get_delay("getMe", &err, 1, Duration::ZERO), None);
+
+        let policy = policy.with_randomization_factor(0.5);
+        let err = ExecuteError::Response(ResponseError::new("Bad Gateway").with_error_code(502));
+        for _ in 0..100 {
+            let delay = policy.get_delay("getMe", &err, 2, Duration::ZERO).unwrap();
+            assert!(delay >= Duration::from_secs(1) && delay <= Duration::from_secs(3));
+        }
+    }
+
+    #[tokio::test]
+    async fn exponential_backoff_idempotent() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let err = reqwest::Client::builder()
+            .timeout(Duration::from_millis(10))
+            .build()
+            .unwrap()
+            .get(format!("http://{}", listener.local_addr().unwrap()))
+            .send()
+            .await
+            .unwrap_err();
+        assert!(err.is_timeout());
+        let err = ExecuteError::Http(err);
+
+        let policy = ExponentialBackoff::default();
+        assert!(policy.get_delay("getMe", &err, 1, Duration::ZERO).is_some());
+        assert!(policy.get_delay("editMessageText", &err, 1, Duration::ZERO).is_some());
+        assert!(policy.get_delay("sendMessage", &err, 1, Duration::ZERO).is_none());
+        assert!(policy.get_delay("forwardMessage", &err, 1, Duration::ZERO).is_none());
+
+        let policy = policy.with_retry_non_idempotent(true);
+        assert!(policy.get_delay("sendMessage", &err, 1, Duration::ZERO).is_some());
+
+        let err = ExecuteError::Response(ResponseError::new("Bad Gateway").with_error_code(502));
+        let policy = ExponentialBackoff::default();
+        assert!(policy.get_delay("getMe", &err, 1, Duration::ZERO).is_some());
+        assert!(policy.get_delay("sendMessage", &err, 1, Duration::ZERO).is_none());
+        let policy = policy.with_retry_non_idempotent(true);
+        assert!(policy.get_delay("sendMessage", &err, 1, Duration::ZERO).is_some());
+    }
+
+    #[test]
+    fn exponential_backoff_overflow() {
+        let err = ExecuteError::Response(ResponseError::new("Bad Gateway").with_error_code(502));
+        let policy = ExponentialBackoff::default()
+            .with_initial_interval(Duration::MAX)
+            .with_max_interval(Duration::MAX)
+            .with_max_elapsed_time(None)
+            .with_multiplier(f64::MAX);
+        assert!(policy.get_delay("getMe", &err, 1, Duration::ZERO).is_some());
+        assert!(policy.get_delay("getMe", &err, u32::MAX, Duration::ZERO).is_some());
+
+        let policy = policy
+            .with_initial_interval(Duration::from_secs(1))
+            .with_max_interval(Duration::from_secs(10))
+            .with_randomization_factor(0.0);
+        assert_eq!(
+            policy.get_delay("getMe", &err, u32::MAX, Duration::ZERO),
+            Some(Duration::from_secs(10))
+        );
+        let policy = policy.with_max_elapsed_time(Some(Duration::from_secs(60)));
+        assert_eq!(policy.get_delay("getMe", &err, 1, Duration::MAX), None);
+    }
+}
@@ -0,0 +1,167 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use reqwest::Error as HttpError;
+
+use crate::types::{Integer, UserPeerId};
+
+const MASK: &str = "***";
+
+/// Represents a token of a bot.
+///
+/// The `Debug` implementation does not reveal the secret part of the token,
+/// so the token can be logged safely.
+///
+/// A token created using [`From`] is not validated; use [`str::parse`] to validate it.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::api::BotToken;
+///
+/// let token: BotToken = "123456:AAHdqTcvCH1vGWJxfSeofSAs0K5PALDsaw".parse().unwrap();
+/// assert_eq!(token.bot_id(), Some(123456.into()));
+/// assert_eq!(format!("{token:?}"), r#"BotToken("123456:***")"#);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BotToken(String);
+
+impl BotToken {
+    /// Returns the identifier of the bot.
+    ///
+    /// Returns `None` when the token has an invalid format.
+    pub fn bot_id(&self) -> Option<UserPeerId> {
+        self.0
+            .split_once(':')
+            .and_then(|(bot_id, _)| bot_id.parse::<Integer>().ok())
+            .map(UserPeerId::from)
+    }
+
+    /// Returns the token including the secret part.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the token with the secret part masked.
+    pub fn masked(&self) -> String {
+        match self.bot_id() {
+            Some(bot_id) => format!("{bot_id}:{MASK}"),
+            None => String::from(MASK),
+        }
+    }
+
+    /// Replaces the token in a text with the masked one.
+    pub(crate) fn redact(&self, value: &str) -> String {
+        if self.0.is_empty() {
+            String::from(value)
+        } else {
+            value.replace(&self.0, &self.masked())
+        }
+    }
+
+    /// Replaces the token in the URL of an error with the masked one.
+    pub(crate) fn redact_error(&self, mut err: HttpError) -> HttpError {
+        if let Some(url) = err.url_mut() {
+            let path = self.redact(url.path());
+            url.set_path(&path);
+        }
+        err
+    }
+}
+
+impl fmt::Debug for BotToken {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_tuple("BotToken").field(&self.masked()).finish()
+    }
+}
+
+impl From<&str> for BotToken {
+    fn from(value: &str) -> Self {
+        Self(String::from(value))
+    }
+}
+
+impl From<String> for BotToken {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl FromStr for BotToken {
+    type Err = BotTokenError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (bot_id, secret) = value.split_once(':').ok_or(BotTokenError::MissingSeparator)?;
+        if bot_id.is_empty() || !bot_id.bytes().all(|x| x.is_ascii_digit()) || bot_id.parse::<Integer>().is_err() {
+            return Err(BotTokenError::InvalidBotId);
+        }
+        if secret.is_empty()
+            || !secret
+                .bytes()
+                .all(|x| x.is_ascii_alphanumeric() || x == b'_' || x == b'-')
+        {
+            return Err(BotTokenError::InvalidSecret);
+        }
+        Ok(Self::from(value))
+    }
+}
+
+/// Represents an error when parsing a bot token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BotTokenError {
+    /// The identifier of the bot is not a number.
+    InvalidBotId,
+    /// The secret part contains invalid characters or is empty.
+    InvalidSecret,
+    /// The token does not contain the `:` separator.
+    MissingSeparator,
+}
+
+impl Error for BotTokenError {}
+
+impl fmt::Display for BotTokenError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            out,
+            "invalid bot token: {}",
+            match self {
+                BotTokenError::InvalidBotId => "bot id is not a number",
+                BotTokenError::InvalidSecret => "secret contains invalid characters",
+                BotTokenError::MissingSeparator => "separator is missing",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bot_token() {
+        let token: BotToken = "123:secret_-AZ09".parse().unwrap();
+        assert_eq!(token.bot_id(), Some(UserPeerId::from(123)));
+        assert_eq!(token.expose_secret(), "123:secret_-AZ09");
+        assert_eq!(token.masked(), "123:***");
+        assert_eq!(format!("{token:?}"), r#"BotToken("123:***")"#);
+        assert_eq!(
+            token.redact("https://api.telegram.org/bot123:secret_-AZ09/getMe"),
+            "https://api.telegram.org/bot123:***/getMe"
+        );
+
+        let token = BotToken::from("-token");
+        assert_eq!(token.bot_id(), None);
+        assert_eq!(token.masked(), "***");
+        assert_eq!(token.redact("/bot-token/getMe"), "/bot***/getMe");
+        assert_eq!(BotToken::from("").redact("/bot/getMe"), "/bot/getMe");
+
+        for (value, err) in [
+            ("token", BotTokenError::MissingSeparator),
+            (":secret", BotTokenError::InvalidBotId),
+            ("12a:secret", BotTokenError::InvalidBotId),
+            ("123:", BotTokenError::InvalidSecret),
+            ("123:sec/ret", BotTokenError::InvalidSecret),
+        ] {
+            assert_eq!(value.parse::<BotToken>().unwrap_err(), err, "{value}");
+        }
+    }
+}
@@ -0,0 +1,447 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    future::Future,
+    io::{Error as IoError, ErrorKind as IoErrorKind},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures_util::future::BoxFuture;
+use log::{debug, error};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Error as JsonError, Value as JsonValue};
+use tokio::{fs, io::AsyncWriteExt, sync::Mutex as AsyncMutex, time::Instant};
+
+use super::UpdateHandler;
+use crate::types::Update;
+
+const DEFAULT_TTL: Duration = Duration::from_secs(86400);
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Represents a storage of dialogue states used by [`Dialogue`].
+///
+/// A key is built according to [`DialogueScope`], e.g. `chat:1` or `1:2`.
+/// A state is stored as a JSON value.
+pub trait DialogueStorage: Send + Sync {
+    /// Returns a state by a key.
+    ///
+    /// Expired states must not be returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the dialogue.
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<JsonValue>, DialogueError>>;
+
+    /// Stores a state.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the dialogue.
+    /// * `state` - The state.
+    /// * `ttl` - The time after which the state expires; `None` - never.
+    fn set(&self, key: String, state: JsonValue, ttl: Option<Duration>) -> BoxFuture<'_, Result<(), DialogueError>>;
+
+    /// Removes a state.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the dialogue.
+    fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), DialogueError>>;
+}
+
+/// Stores dialogue states in memory.
+///
+/// An expired state is removed when it is requested;
+/// other expired states are removed when a state is stored, at most once a minute.
+#[derive(Debug, Default)]
+pub struct InMemoryDialogueStorage {
+    items: Mutex<InMemoryItems>,
+}
+
+#[derive(Debug, Default)]
+struct InMemoryItems {
+    states: HashMap<String, (JsonValue, Option<Instant>)>,
+    swept_at: Option<Instant>,
+}
+
+impl DialogueStorage for InMemoryDialogueStorage {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<JsonValue>, DialogueError>> {
+        let items = &mut self.items.lock().unwrap().states;
+        let state = match items.get(key) {
+            Some((_, Some(expires_at))) if *expires_at <= Instant::now() => {
+                items.remove(key);
+                None
+            }
+            Some((state, _)) => Some(state.clone()),
+            None => None,
+        };
+        Box::pin(async move { Ok(state) })
+    }
+
+    fn set(&self, key: String, state: JsonValue, ttl: Option<Duration>) -> BoxFuture<'_, Result<(), DialogueError>> {
+        let now = Instant::now();
+        let mut items = self.items.lock().unwrap();
+        if items.swept_at.is_none_or(|x| now - x >= SWEEP_INTERVAL) {
+            items
+                .states
+                .retain(|_, (_, expires_at)| expires_at.is_none_or(|x| x > now));
+            items.swept_at = Some(now);
+        }
+        items.states.insert(key, (state, ttl.map(|ttl| now + ttl)));
+        Box::pin(async { Ok(()) })
+    }
+
+    fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), DialogueError>> {
+        self.items.lock().unwrap().states.remove(key);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Stores dialogue states in a JSON file.
+///
+/// The file is written after each change; expired states are removed when a state is stored.
+/// The data is written to a temporary file next to the target, which is then renamed,
+/// so the file is not corrupted when the process is stopped while writing.
+#[derive(Debug)]
+pub struct JsonFileDialogueStorage {
+    path: PathBuf,
+    items: AsyncMutex<HashMap<String, JsonFileEntry>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct JsonFileEntry {
+    state: JsonValue,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires_at: Option<u64>,
+}
+
+impl JsonFileEntry {
+    fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|x| x <= now)
+    }
+}
+
+impl JsonFileDialogueStorage {
+    /// Opens a storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the file; created when a state is stored.
+    pub async fn open<T>(path: T) -> Result<Self, DialogueError>
+    where
+        T: Into<PathBuf>,
+    {
+        let path = path.into();
+        let items = match fs::read(&path).await {
+            Ok(data) => serde_json::from_slice(&data)?,
+            Err(err) if err.kind() == IoErrorKind::NotFound => HashMap::new(),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Self {
+            path,
+            items: AsyncMutex::new(items),
+        })
+    }
+
+    /// Returns the path to the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    async fn write(&self, items: &HashMap<String, JsonFileEntry>) -> Result<(), DialogueError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let data = serde_json::to_vec(items)?;
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let mut file = fs::File::create(&tmp_path).await?;
+        file.write_all(&data).await?;
+        file.sync_all().await?;
+        fs::rename(&tmp_path, &self.path).await?;
+        Ok(())
+    }
+}
+
+impl DialogueStorage for JsonFileDialogueStorage {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<JsonValue>, DialogueError>> {
+        Box::pin(async move {
+            let items = self.items.lock().await;
+            Ok(items
+                .get(key)
+                .filter(|entry| !entry.is_expired(get_unix_time()))
+                .map(|entry| entry.state.clone()))
+        })
+    }
+
+    fn set(&self, key: String, state: JsonValue, ttl: Option<Duration>) -> BoxFuture<'_, Result<(), DialogueError>> {
+        Box::pin(async move {
+            let now = get_unix_time();
+            let mut items = self.items.lock().await;
+            items.retain(|_, entry| !entry.is_expired(now));
+            items.insert(
+                key,
+                JsonFileEntry {
+                    state,
+                    expires_at: ttl.map(|ttl| now + ttl.as_secs()),
+                },
+            );
+            self.write(&items).await
+        })
+    }
+
+    fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), DialogueError>> {
+        Box::pin(async move {
+            let mut items = self.items.lock().await;
+            if items.remove(key).is_some() {
+                self.write(&items).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+fn get_unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or_default()
+}
+
+/// Represents a scope of a dialogue.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DialogueScope {
+    /// A dialogue per chat; the key is `chat:<chat_id>`.
+    Chat,
+    /// A dialogue per user in a chat; the key is `<chat_id>:<user_id>`.
+    #[default]
+    ChatUser,
+    /// A dialogue per user; the key is `user:<user_id>`.
+    User,
+}
+
+impl DialogueScope {
+    fn get_key(self, update: &Update) -> Option<String> {
+        match self {
+            DialogueScope::Chat => update.get_chat_id().map(|chat_id| format!("chat:{chat_id}")),
+            DialogueScope::ChatUser => update
+                .get_chat_id()
+                .zip(update.get_user_id())
+                .map(|(chat_id, user_id)| format!("{chat_id}:{user_id}")),
+            DialogueScope::User => update.get_user_id().map(|user_id| format!("user:{user_id}")),
+        }
+    }
+}
+
+/// An update handler which keeps a state of a dialogue between updates.
+///
+/// For each update, the state is loaded from the storage using a key from [`DialogueScope`],
+/// and passed to the handler along with the update.
+/// The handler returns the next state which is saved, or `None` to finish the dialogue.
+/// When there is no state, [`Default`] is used.
+///
+/// Updates with the same key are handled one by one by the same `Dialogue`,
+/// so a state is not overwritten by concurrent updates;
+/// this does not apply to different `Dialogue` instances sharing a storage.
+///
+/// Updates without a chat or a user required by the scope are skipped.
+/// When a stored state can not be parsed, e.g. after the state type has changed,
+/// an error is logged and updates of the dialogue are skipped until the state is removed or expires.
+///
+/// # Example
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use tgbot::{
+///     handler::{Dialogue, InMemoryDialogueStorage},
+///     types::Update,
+/// };
+///
+/// #[derive(Default, Deserialize, Serialize)]
+/// enum State {
+///     #[default]
+///     Start,
+///     ReceiveName,
+///     ReceiveAge { name: String },
+/// }
+///
+/// async fn handle(update: Update, state: State) -> Option<State> {
+///     let text = update.get_message().and_then(|x| x.get_text()).map(|x| x.data.clone());
+///     match (state, text) {
+///         (State::Start, _) => Some(State::ReceiveName),
+///         (State::ReceiveName, Some(name)) => Some(State::ReceiveAge { name }),
+///         (State::ReceiveAge { name }, Some(age)) => {
+///             println!("Name: {name}, age: {age}");
+///             None
+///         }
+///         (state, None) => Some(state),
+///     }
+/// }
+///
+/// let dialogue = Dialogue::new(InMemoryDialogueStorage::default(), handle);
+/// ```
+pub struct Dialogue<S, H, T = InMemoryDialogueStorage> {
+    storage: T,
+    handler: H,
+    scope: DialogueScope,
+    ttl: Option<Duration>,
+    locks: Mutex<HashMap<String, Arc<AsyncMutex<()>>>>,
+    state: PhantomData<fn() -> S>,
+}
+
+impl<S, H, T> Dialogue<S, H, T> {
+    /// Creates a new `Dialogue`.
+    ///
+    /// # Arguments
+    ///
+    /// * `storage` - The storage of states.
+    /// * `handler` - A function which receives an update and the current state,
+    ///   and returns the next state.
+    pub fn new<F>(storage: T, handler: H) -> Self
+    where
+        H: Fn(Update, S) -> F,
+    {
+        Self {
+            storage,
+            handler,
+            scope: DialogueScope::default(),
+            ttl: Some(DEFAULT_TTL),
+            locks: Default::default(),
+            state: PhantomData,
+        }
+    }
+
+    /// Sets a new scope.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The scope; default - [`DialogueScope::ChatUser`].
+    pub fn with_scope(mut self, value: DialogueScope) -> Self {
+        self.scope = value;
+        self
+    }
+
+    /// Sets a new time after which an inactive dialogue expires.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The time; default - 1 day; `None` - dialogues never expire.
+    pub fn with_ttl(mut self, value: Option<Duration>) -> Self {
+        self.ttl = value;
+        self
+    }
+
+    /// Returns the storage.
+    pub fn storage(&self) -> &T {
+        &self.storage
+    }
+}
+
+impl<S, H, F, T> UpdateHandler for Dialogue<S, H, T>
+where
+    S: Default + DeserializeOwned + Serialize + Send,
+    H: Fn(Update, S) -> F + Send + Sync,
+    F: Future<Output = Option<S>> + Send,
+    T: DialogueStorage,
+{
+    async fn handle(&self, update: Update) {
+        let Some(key) = self.scope.get_key(&update) else {
+            debug!("Skipping update {} without a dialogue key", update.id);
+            return;
+        };
+        let lock = self.locks.lock().unwrap().entry(key.clone()).or_default().clone();
+        {
+            let _guard = lock.lock().await;
+            self.handle_dialogue(&key, update).await;
+        }
+        let mut locks = self.locks.lock().unwrap();
+        // The lock is not used by other updates when it is referenced only by the map and this call.
+        if Arc::strong_count(&lock) == 2 {
+            locks.remove(&key);
+        }
+    }
+}
+
+impl<S, H, F, T> Dialogue<S, H, T>
+where
+    S: Default + DeserializeOwned + Serialize + Send,
+    H: Fn(Update, S) -> F + Send + Sync,
+    F: Future<Output = Option<S>> + Send,
+    T: DialogueStorage,
+{
+    async fn handle_dialogue(&self, key: &str, update: Update) {
+        let state = match self.storage.get(key).await {
+            Ok(Some(state)) => match serde_json::from_value(state) {
+                Ok(state) => state,
+                Err(err) => {
+                    let err = DialogueError::from(err);
+                    error!("Could not load dialogue {key}, skipping update {}: {err}", update.id);
+                    return;
+                }
+            },
+            Ok(None) => S::default(),
+            Err(err) => {
+                error!("Could not load dialogue {key}: {err}");
+                return;
+            }
+        };
+        let result = match (self.handler)(update, state).await {
+            Some(state) => match serde_json::to_value(state) {
+                Ok(state) => self.storage.set(key.to_string(), state, self.ttl).await,
+                Err(err) => Err(err.into()),
+            },
+            None => self.storage.remove(key).await,
+        };
+        if let Err(err) = result {
+            error!("Could not save dialogue {key}: {err}");
+        }
+    }
+}
+
+impl<S, H, T> fmt::Debug for Dialogue<S, H, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_struct("Dialogue")
+            .field("storage", &self.storage)
+            .field("scope", &self.scope)
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+/// Represents an error of a dialogue storage.
+#[derive(Debug, derive_more::From)]
+pub enum DialogueError {
+    /// Failed to read or write a file.
+    Io(IoError),
+    /// Failed to serialize or deserialize a state.
+    Json(JsonError),
+    /// An error of a custom storage.
+    Storage(Box<dyn Error + Send + Sync>),
+}
+
+impl Error for DialogueError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(match self {
+            DialogueError::Io(err) => err,
+            DialogueError::Json(err) => err,
+            DialogueError::Storage(err) => err.as_ref(),
+        })
+    }
+}
+
+impl fmt::Display for DialogueError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DialogueError::Io(err) => write!(out, "dialogue storage IO error: {err}"),
+            DialogueError::Json(err) => write!(out, "can not serialize or parse dialogue state: {err}"),
+            DialogueError::Storage(err) => write!(out, "dialogue storage error: {err}"),
+        }
+    }
+}
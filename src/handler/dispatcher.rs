@@ -0,0 +1,136 @@
+use std::{fmt, future::Future, sync::Arc};
+
+use futures_util::future::{BoxFuture, join_all};
+use log::debug;
+
+use super::{UpdateHandler, filter::Filter};
+use crate::types::Update;
+
+type Handler = Arc<dyn Fn(Update) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// Represents a mode of routing updates in [`Dispatcher`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DispatchMode {
+    /// An update is passed to the first matching handler.
+    #[default]
+    FirstMatch,
+    /// An update is passed to all matching handlers concurrently.
+    FanOut,
+}
+
+/// Routes updates to handlers using filters.
+///
+/// Routes are checked in the order they were added.
+/// When no route matches, the update is passed to the default handler, if any.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::{
+///     handler::{Dispatcher, Filter},
+///     types::CallbackQuery,
+/// };
+///
+/// let dispatcher = Dispatcher::default()
+///     .with_route(Filter::command("start"), |update| async move {
+///         println!("Start: {update:?}");
+///     })
+///     .with_route(Filter::kind::<CallbackQuery>(), |update| async move {
+///         println!("Callback query: {update:?}");
+///     })
+///     .with_default_handler(|update| async move {
+///         println!("Unhandled: {update:?}");
+///     });
+/// ```
+#[derive(Clone, Default)]
+pub struct Dispatcher {
+    routes: Vec<(Filter, Handler)>,
+    default_handler: Option<Handler>,
+    mode: DispatchMode,
+}
+
+impl Dispatcher {
+    /// Adds a route.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The filter of updates.
+    /// * `handler` - The handler of matching updates.
+    pub fn with_route<H, F>(mut self, filter: Filter, handler: H) -> Self
+    where
+        H: Fn(Update) -> F + Send + Sync + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.routes.push((filter, into_handler(handler)));
+        self
+    }
+
+    /// Sets a handler of updates not matched by any route.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler.
+    pub fn with_default_handler<H, F>(mut self, handler: H) -> Self
+    where
+        H: Fn(Update) -> F + Send + Sync + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.default_handler = Some(into_handler(handler));
+        self
+    }
+
+    /// Sets a mode of routing.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The mode; default - [`DispatchMode::FirstMatch`].
+    pub fn with_mode(mut self, value: DispatchMode) -> Self {
+        self.mode = value;
+        self
+    }
+}
+
+impl UpdateHandler for Dispatcher {
+    async fn handle(&self, update: Update) {
+        let mut handlers = self
+            .routes
+            .iter()
+            .filter(|(filter, _)| filter.check(&update))
+            .map(|(_, handler)| handler);
+        match self.mode {
+            DispatchMode::FirstMatch => match handlers.next().or(self.default_handler.as_ref()) {
+                Some(handler) => handler(update).await,
+                None => debug!("No handler found for update {}", update.id),
+            },
+            DispatchMode::FanOut => {
+                let handlers: Vec<_> = handlers.collect();
+                if handlers.is_empty() {
+                    match &self.default_handler {
+                        Some(handler) => handler(update).await,
+                        None => debug!("No handler found for update {}", update.id),
+                    }
+                } else {
+                    join_all(handlers.into_iter().map(|handler| handler(update.clone()))).await;
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Dispatcher {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_struct("Dispatcher")
+            .field("routes", &self.routes.len())
+            .field("default_handler", &self.default_handler.is_some())
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+
+fn into_handler<H, F>(handler: H) -> Handler
+where
+    H: Fn(Update) -> F + Send + Sync + 'static,
+    F: Future<Output = ()> + Send + 'static,
+{
+    Arc::new(move |update| Box::pin(handler(update)))
+}
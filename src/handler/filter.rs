@@ -0,0 +1,354 @@
+use std::{
+    fmt,
+    ops::{BitAnd, BitOr, Not},
+    sync::Arc,
+};
+
+use regex::Regex;
+
+use crate::types::{Chat, Text, TextEntity, Update, UpdateKind, UpdateType, UserPeerId};
+
+/// Represents a kind of a chat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChatKind {
+    /// A channel chat.
+    Channel,
+    /// A group chat.
+    Group,
+    /// A private chat.
+    Private,
+    /// A supergroup chat.
+    Supergroup,
+}
+
+impl From<&Chat> for ChatKind {
+    fn from(value: &Chat) -> Self {
+        match value {
+            Chat::Channel(_) => Self::Channel,
+            Chat::Group(_) => Self::Group,
+            Chat::Private(_) => Self::Private,
+            Chat::Supergroup(_) => Self::Supergroup,
+        }
+    }
+}
+
+/// Represents a predicate used by [`crate::handler::Dispatcher`] to route updates.
+///
+/// Filters can be combined using [`Self::and`], [`Self::or`] and `!`.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::handler::{ChatKind, Filter};
+///
+/// let filter = Filter::command("start").and(Filter::chat_kind(ChatKind::Private));
+/// let filter = filter.or(Filter::callback_data_prefix("start:"));
+/// let filter = !Filter::user_id(1).and(filter);
+/// ```
+#[derive(Clone)]
+pub struct Filter(Arc<dyn Fn(&Update) -> bool + Send + Sync>);
+
+impl Filter {
+    /// Creates a new `Filter` from a function.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A function which returns `true` when an update matches.
+    pub fn new<F>(value: F) -> Self
+    where
+        F: Fn(&Update) -> bool + Send + Sync + 'static,
+    {
+        Self(Arc::new(value))
+    }
+
+    /// Creates a filter which matches all updates.
+    pub fn any() -> Self {
+        Self::new(|_| true)
+    }
+
+    /// Creates a filter which matches updates convertible to `T`,
+    /// e.g. [`crate::types::Message`] or [`crate::types::CallbackQuery`].
+    pub fn kind<T>() -> Self
+    where
+        T: UpdateKind,
+    {
+        Self::new(|update| T::matches(&update.update_type))
+    }
+
+    /// Creates a filter which matches messages starting with a command.
+    ///
+    /// Commands addressed to a bot, e.g. `/start@bot_name`, are not matched;
+    /// use [`Self::command_for_bot`] to match them.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the command with or without leading slash.
+    pub fn command<T>(name: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::new_command(name.into(), None)
+    }
+
+    /// Creates a filter which matches messages starting with a command
+    /// which is either not addressed to any bot or addressed to the given bot.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the command with or without leading slash.
+    /// * `bot_username` - The username of the bot with or without leading `@`.
+    pub fn command_for_bot<A, B>(name: A, bot_username: B) -> Self
+    where
+        A: Into<String>,
+        B: Into<String>,
+    {
+        let bot_username = bot_username.into();
+        let bot_username = bot_username.strip_prefix('@').unwrap_or(&bot_username).to_string();
+        Self::new_command(name.into(), Some(bot_username))
+    }
+
+    fn new_command(name: String, bot_username: Option<String>) -> Self {
+        let name = name.strip_prefix('/').unwrap_or(&name).to_string();
+        Self::new(move |update| {
+            let Some((command, bot_name)) = update
+                .get_message()
+                .and_then(|message| message.get_text())
+                .and_then(get_leading_command)
+            else {
+                return false;
+            };
+            command == name
+                && match (bot_name, &bot_username) {
+                    (None, _) => true,
+                    (Some(bot_name), Some(bot_username)) => bot_name.eq_ignore_ascii_case(bot_username),
+                    (Some(_), None) => false,
+                }
+        })
+    }
+
+    /// Creates a filter which matches callback queries with data starting with a prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix of the data.
+    pub fn callback_data_prefix<T>(prefix: T) -> Self
+    where
+        T: Into<String>,
+    {
+        let prefix = prefix.into();
+        Self::new(move |update| match &update.update_type {
+            UpdateType::CallbackQuery(query) => query.data.as_ref().is_some_and(|data| data.starts_with(&prefix)),
+            _ => false,
+        })
+    }
+
+    /// Creates a filter which matches updates from a kind of chats.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of the chat.
+    pub fn chat_kind(kind: ChatKind) -> Self {
+        Self::new(move |update| update.get_chat().is_some_and(|chat| ChatKind::from(chat) == kind))
+    }
+
+    /// Creates a filter which matches updates from a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    pub fn user_id<T>(user_id: T) -> Self
+    where
+        T: Into<UserPeerId>,
+    {
+        let user_id = user_id.into();
+        Self::new(move |update| update.get_user_id() == Some(user_id))
+    }
+
+    /// Creates a filter which matches messages with a text or a caption matching a regular expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `regex` - The regular expression.
+    pub fn text_regex(regex: Regex) -> Self {
+        Self::new(move |update| {
+            update
+                .get_message()
+                .and_then(|message| message.get_text())
+                .is_some_and(|text| regex.is_match(&text.data))
+        })
+    }
+
+    /// Returns a filter which matches when both filters match.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other filter.
+    pub fn and(self, other: Filter) -> Self {
+        Self::new(move |update| self.check(update) && other.check(update))
+    }
+
+    /// Returns a filter which matches when any of the filters matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other filter.
+    pub fn or(self, other: Filter) -> Self {
+        Self::new(move |update| self.check(update) || other.check(update))
+    }
+
+    /// Returns whether an update matches the filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The update to check.
+    pub fn check(&self, update: &Update) -> bool {
+        (self.0)(update)
+    }
+}
+
+/// Returns the name and the bot name of a command at the start of a text.
+fn get_leading_command(text: &Text) -> Option<(String, Option<String>)> {
+    let position = text.entities.as_ref()?.into_iter().find_map(|entity| match entity {
+        TextEntity::BotCommand(position) if position.offset == 0 => Some(*position),
+        _ => None,
+    })?;
+    // position is UTF-16 offset
+    let command: Vec<u16> = text.data.encode_utf16().take(position.length as usize).collect();
+    let command = String::from_utf16_lossy(&command);
+    let command = command.strip_prefix('/')?;
+    Some(match command.split_once('@') {
+        Some((command, bot_name)) => (command.to_string(), Some(bot_name.to_string())),
+        None => (command.to_string(), None),
+    })
+}
+
+impl BitAnd for Filter {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.and(rhs)
+    }
+}
+
+impl BitOr for Filter {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.or(rhs)
+    }
+}
+
+impl Not for Filter {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self::new(move |update| !self.check(update))
+    }
+}
+
+impl fmt::Debug for Filter {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_tuple("Filter").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::{CallbackQuery, ChatMemberUpdated, Message};
+
+    fn message(text: &str, chat_type: &str) -> Update {
+        let entities = match text.find('/') {
+            Some(offset) => {
+                let length = text[offset..].find(' ').unwrap_or(text.len() - offset);
+                json!([{"type": "bot_command", "offset": offset, "length": length}])
+            }
+            None => json!([]),
+        };
+        serde_json::from_value(json!({
+            "update_id": 1,
+            "message": {
+                "message_id": 1,
+                "date": 0,
+                "from": {"id": 1, "is_bot": false, "first_name": "John"},
+                "chat": {"id": 1, "type": chat_type, "first_name": "John", "title": "Chat"},
+                "text": text,
+                "entities": entities
+            }
+        }))
+        .unwrap()
+    }
+
+    fn callback_query(data: &str) -> Update {
+        serde_json::from_value(json!({
+            "update_id": 1,
+            "callback_query": {
+                "id": "id",
+                "from": {"id": 2, "is_bot": false, "first_name": "Jane"},
+                "chat_instance": "instance",
+                "data": data
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn filters() {
+        let start = message("/start arg", "private");
+        let text = message("hello world", "group");
+        let query = callback_query("page:2");
+
+        assert!(Filter::any().check(&start));
+        assert!(Filter::kind::<Message>().check(&start));
+        assert!(!Filter::kind::<CallbackQuery>().check(&start));
+        assert!(Filter::kind::<CallbackQuery>().check(&query));
+        assert!(!Filter::kind::<ChatMemberUpdated>().check(&query));
+
+        assert!(Filter::command("start").check(&start));
+        assert!(Filter::command("/start").check(&start));
+        assert!(!Filter::command("stop").check(&start));
+        assert!(!Filter::command("start").check(&text));
+        assert!(!Filter::command("start").check(&message("text /start", "private")));
+        assert!(Filter::command_for_bot("start", "@bot").check(&start));
+
+        let mention = message("/start@Bot arg", "group");
+        assert!(!Filter::command("start").check(&mention));
+        assert!(Filter::command_for_bot("start", "@bot").check(&mention));
+        assert!(Filter::command_for_bot("/start", "Bot").check(&mention));
+        assert!(!Filter::command_for_bot("start", "other_bot").check(&mention));
+        assert!(!Filter::command_for_bot("stop", "bot").check(&mention));
+
+        assert!(Filter::callback_data_prefix("page:").check(&query));
+        assert!(!Filter::callback_data_prefix("item:").check(&query));
+        assert!(!Filter::callback_data_prefix("page:").check(&start));
+
+        assert!(Filter::chat_kind(ChatKind::Private).check(&start));
+        assert!(Filter::chat_kind(ChatKind::Group).check(&text));
+        assert!(!Filter::chat_kind(ChatKind::Private).check(&query));
+
+        assert!(Filter::user_id(1).check(&start));
+        assert!(Filter::user_id(2).check(&query));
+        assert!(!Filter::user_id(2).check(&start));
+
+        let regex = Regex::new("^hello").unwrap();
+        assert!(Filter::text_regex(regex.clone()).check(&text));
+        assert!(!Filter::text_regex(regex).check(&start));
+
+        let filter = Filter::command("start").and(Filter::chat_kind(ChatKind::Private));
+        assert!(filter.check(&start));
+        assert!(!filter.check(&text));
+        let filter = Filter::command("start").or(Filter::callback_data_prefix("page:"));
+        assert!(filter.check(&start));
+        assert!(filter.check(&query));
+        assert!(!filter.check(&text));
+        let filter = !Filter::kind::<Message>();
+        assert!(!filter.check(&start));
+        assert!(filter.check(&query));
+        let filter = Filter::user_id(1) & !Filter::command("start") | Filter::user_id(2);
+        assert!(!filter.check(&start));
+        assert!(filter.check(&text));
+        assert!(filter.check(&query));
+    }
+}
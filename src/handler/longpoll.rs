@@ -1,34 +1,49 @@
-use std::{cmp::max, collections::HashSet, sync::Arc, time::Duration};
+use std::{
+    cmp::max,
+    collections::{BTreeMap, HashSet},
+    future::Future,
+    pin::pin,
+    sync::{
+        Arc,
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
 
-use async_stream::stream;
-use futures_util::{pin_mut, stream::StreamExt};
+use futures_util::future::{Either, select};
 use log::error;
 use tokio::{
     spawn,
     sync::{
+        Notify,
         Semaphore,
         mpsc::{Receiver, Sender, channel},
+        oneshot,
     },
-    time::sleep,
+    time::{sleep, timeout},
 };
 
 use crate::{
     api::{Client, ExecuteError},
-    handler::UpdateHandler,
-    types::{AllowedUpdate, GetUpdates, Integer},
+    handler::{OrderedHandler, UpdateHandler, UpdateOrdering},
+    types::{AllowedUpdate, GetUpdates, Integer, Update},
 };
 
 const DEFAULT_LIMIT: Integer = 100;
 const DEFAULT_POLL_TIMEOUT: Duration = Duration::from_secs(10);
 const DEFAULT_ERROR_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+type ShutdownSender = oneshot::Sender<LongPollSummary>;
 
 /// Allows receiving incoming updates from the Telegram Bot API using long polling.
 pub struct LongPoll<H> {
     client: Client,
     handler: Arc<H>,
     options: LongPollOptions,
-    sender: Sender<()>,
-    receiver: Receiver<()>,
+    sender: Sender<ShutdownSender>,
+    receiver: Receiver<ShutdownSender>,
 }
 
 impl<H> LongPoll<H> {
@@ -73,7 +88,24 @@ where
     }
 
     /// Starts the polling loop.
-    pub async fn run(self) {
+    ///
+    /// The loop runs until [`LongPollHandle::shutdown`] is called.
+    /// On shutdown, it stops getting updates, waits for running handlers
+    /// and confirms the offset of handled updates, so they are not received again.
+    ///
+    /// Handlers that are still running after the shutdown timeout are not cancelled
+    /// and may complete after `run` returns, but their updates are not confirmed
+    /// and are received again on the next start.
+    /// Updates waiting in queues of [`LongPollOptions::with_ordering`] are dropped
+    /// on the shutdown timeout and are received again as well.
+    pub async fn run(self) -> LongPollSummary {
+        let Self {
+            client,
+            handler,
+            options,
+            sender: _sender,
+            mut receiver,
+        } = self;
         let LongPollOptions {
             mut offset,
             limit,
@@ -81,59 +113,203 @@ where
             error_timeout,
             allowed_updates,
             concurrency_limit,
-        } = self.options;
+            ordering,
+            shutdown_timeout,
+        } = options;
 
         let semaphore = Arc::new(Semaphore::new(concurrency_limit.unwrap_or(Semaphore::MAX_PERMITS)));
+        let progress = Arc::new(Progress::default());
+        let handler = Arc::new(TrackedHandler {
+            handler,
+            progress: progress.clone(),
+        });
+        let ordered_handler = ordering
+            .map(|ordering| OrderedHandler::from_arc(handler.clone(), ordering).with_semaphore(semaphore.clone()));
 
-        let client = self.client.clone();
-        let mut receiver = self.receiver;
-        let s = stream! {
-            loop {
-                if receiver.try_recv().is_ok() {
-                    receiver.close();
-                    break;
-                }
-                let method = GetUpdates::default()
-                    .with_allowed_updates(allowed_updates.clone())
-                    .with_limit(limit)
-                    .with_offset(offset + 1)
-                    .with_timeout(poll_timeout);
-                let updates = match client.execute(method).await {
-                    Ok(updates) => updates,
-                    Err(err) => {
-                        error!("An error has occurred while getting updates: {err}");
-                        let error_timeout = get_error_timeout(err, error_timeout);
-                        sleep(error_timeout).await;
-                        continue
+        let mut received = 0;
+        let summary_sender = loop {
+            let method = GetUpdates::default()
+                .with_allowed_updates(allowed_updates.clone())
+                .with_limit(limit)
+                .with_offset(offset + 1)
+                .with_timeout(poll_timeout);
+            let updates = match recv_or(&mut receiver, client.execute(method)).await {
+                Either::Left(summary_sender) => break summary_sender,
+                Either::Right(Ok(updates)) => updates,
+                Either::Right(Err(err)) => {
+                    error!("An error has occurred while getting updates: {err}");
+                    let error_timeout = get_error_timeout(err, error_timeout);
+                    match recv_or(&mut receiver, sleep(error_timeout)).await {
+                        Either::Left(summary_sender) => break summary_sender,
+                        Either::Right(_) => continue,
                     }
-                };
-                for update in updates {
-                    offset = max(offset, update.id);
-                    yield update
                 }
+            };
+            for update in updates {
+                offset = max(offset, update.id);
+                received += 1;
+                progress.start(update.id);
+                if let Some(ordered_handler) = &ordered_handler {
+                    ordered_handler.handle(update).await;
+                    continue;
+                }
+                let handler = handler.clone();
+                let permit = semaphore.clone().acquire_owned().await.unwrap();
+                spawn(async move {
+                    let _guard = permit;
+                    handler.handle(update).await;
+                });
             }
         };
-        pin_mut!(s);
-        while let Some(update) = s.next().await {
-            let handler = self.handler.clone();
-            let permit = semaphore.clone().acquire_owned().await.unwrap();
-            spawn(async move {
-                let _guard = permit;
-                handler.handle(update).await;
-            });
+        receiver.close();
+
+        if timeout(shutdown_timeout, progress.wait()).await.is_err() {
+            error!("Handlers have not completed within the shutdown timeout");
+            if let Some(ordered_handler) = &ordered_handler {
+                ordered_handler.close();
+            }
+        }
+        let completed = progress.completed.load(Ordering::Acquire);
+        let confirmed_offset = if received > 0 {
+            let confirmed_offset = progress.first_pending().unwrap_or(offset + 1);
+            let method = GetUpdates::default()
+                .with_allowed_updates(allowed_updates)
+                .with_limit(1)
+                .with_offset(confirmed_offset)
+                .with_timeout(Duration::ZERO);
+            match client.execute(method).await {
+                Ok(_) => Some(confirmed_offset),
+                Err(err) => {
+                    error!("An error has occurred while confirming offset {confirmed_offset}: {err}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let summary = LongPollSummary {
+            received,
+            completed,
+            abandoned: received - completed,
+            confirmed_offset,
+        };
+        if let Some(summary_sender) = summary_sender {
+            let _ = summary_sender.send(summary);
         }
+        summary
     }
 }
 
+/// Represents a result of a polling loop.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LongPollSummary {
+    /// Number of received updates.
+    pub received: usize,
+    /// Number of updates handled before the shutdown timeout,
+    /// including updates whose handler has panicked.
+    pub completed: usize,
+    /// Number of updates still being handled or dropped from queues after the shutdown timeout.
+    pub abandoned: usize,
+    /// The offset confirmed on shutdown;
+    /// `None` if no updates were received or the request has failed.
+    ///
+    /// It is the ID of the earliest update which has not been handled,
+    /// so updates handled after it are received again on the next start;
+    /// when all updates are handled, it is the ID of the last update + 1.
+    pub confirmed_offset: Option<Integer>,
+}
+
 /// Allows to control a polling loop.
 pub struct LongPollHandle {
-    sender: Sender<()>,
+    sender: Sender<ShutdownSender>,
 }
 
 impl LongPollHandle {
     /// Stops the associated polling loop.
-    pub async fn shutdown(self) {
-        let _ = self.sender.send(()).await;
+    ///
+    /// Waits until the loop has completed the shutdown, see [`LongPoll::run`].
+    ///
+    /// Returns `None` if the loop has already been stopped.
+    pub async fn shutdown(self) -> Option<LongPollSummary> {
+        let (sender, receiver) = oneshot::channel();
+        self.sender.send(sender).await.ok()?;
+        receiver.await.ok()
+    }
+}
+
+#[derive(Default)]
+struct Progress {
+    pending: Mutex<BTreeMap<Integer, usize>>,
+    completed: AtomicUsize,
+    notify: Notify,
+}
+
+impl Progress {
+    fn start(&self, update_id: Integer) {
+        *self.pending.lock().unwrap().entry(update_id).or_default() += 1;
+    }
+
+    fn finish(&self, update_id: Integer) {
+        let mut pending = self.pending.lock().unwrap();
+        if let Some(count) = pending.get_mut(&update_id) {
+            *count -= 1;
+            if *count == 0 {
+                pending.remove(&update_id);
+            }
+        }
+        self.completed.fetch_add(1, Ordering::AcqRel);
+        self.notify.notify_one();
+    }
+
+    fn first_pending(&self) -> Option<Integer> {
+        self.pending.lock().unwrap().keys().next().copied()
+    }
+
+    async fn wait(&self) {
+        while self.first_pending().is_some() {
+            self.notify.notified().await;
+        }
+    }
+}
+
+struct TrackedHandler<H> {
+    handler: Arc<H>,
+    progress: Arc<Progress>,
+}
+
+impl<H> UpdateHandler for TrackedHandler<H>
+where
+    H: UpdateHandler + Send + Sync,
+{
+    async fn handle(&self, update: Update) {
+        let _guard = FinishGuard {
+            progress: &self.progress,
+            update_id: update.id,
+        };
+        self.handler.handle(update).await;
+    }
+}
+
+/// Marks an update as finished when dropped, so a panicking handler does not leave it pending.
+struct FinishGuard<'a> {
+    progress: &'a Progress,
+    update_id: Integer,
+}
+
+impl Drop for FinishGuard<'_> {
+    fn drop(&mut self) {
+        self.progress.finish(self.update_id);
+    }
+}
+
+/// Waits for a shutdown request or for a future to complete, whichever comes first.
+async fn recv_or<F>(receiver: &mut Receiver<ShutdownSender>, future: F) -> Either<Option<ShutdownSender>, F::Output>
+where
+    F: Future,
+{
+    match select(pin!(receiver.recv()), pin!(future)).await {
+        Either::Left((value, _)) => Either::Left(value),
+        Either::Right((value, _)) => Either::Right(value),
     }
 }
 
@@ -153,6 +329,8 @@ pub struct LongPollOptions {
     error_timeout: Duration,
     allowed_updates: HashSet<AllowedUpdate>,
     concurrency_limit: Option<usize>,
+    ordering: Option<UpdateOrdering>,
+    shutdown_timeout: Duration,
 }
 
 impl LongPollOptions {
@@ -198,6 +376,33 @@ impl LongPollOptions {
         self
     }
 
+    /// Sets a new ordering of update processing.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The ordering; by default, all updates are handled concurrently.
+    ///
+    /// The concurrency limit applies to handlers of all keys.
+    ///
+    /// Updates are queued from the polling loop: when a queue of a key is full,
+    /// the loop stops getting updates until the handler of the key catches up,
+    /// so a slow or busy key delays updates of all other keys.
+    /// Set a queue size with [`UpdateOrdering::with_queue_size`] large enough for bursts of a single key.
+    pub fn with_ordering(mut self, value: UpdateOrdering) -> Self {
+        self.ordering = Some(value);
+        self
+    }
+
+    /// Sets a new shutdown timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Maximum time to wait for running handlers on shutdown; default - 30 seconds.
+    pub fn with_shutdown_timeout(mut self, value: Duration) -> Self {
+        self.shutdown_timeout = value;
+        self
+    }
+
     /// Sets a new timeout for long polling.
     ///
     /// # Arguments
@@ -222,6 +427,8 @@ impl Default for LongPollOptions {
             error_timeout: DEFAULT_ERROR_TIMEOUT,
             allowed_updates: HashSet::new(),
             concurrency_limit: None,
+            ordering: None,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
         }
     }
 }
@@ -1,11 +1,15 @@
 use std::{future::Future, sync::Arc};
 
-pub use self::longpoll::*;
 #[cfg(feature = "webhook")]
 pub use self::webhook::*;
+pub use self::{dialogue::*, dispatcher::*, filter::*, longpoll::*, ordering::*};
 use crate::types::Update;
 
+mod dialogue;
+mod dispatcher;
+mod filter;
 mod longpoll;
+mod ordering;
 
 #[cfg(feature = "webhook")]
 mod webhook;
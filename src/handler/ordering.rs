@@ -0,0 +1,402 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{
+        Arc,
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use log::error;
+use tokio::{
+    spawn,
+    sync::{
+        Semaphore,
+        SemaphorePermit,
+        mpsc::{Receiver, Sender, channel},
+    },
+};
+
+use super::UpdateHandler;
+use crate::types::Update;
+
+const DEFAULT_QUEUE_SIZE: usize = 100;
+
+type KeyFn = Arc<dyn Fn(&Update) -> Option<String> + Send + Sync>;
+
+#[derive(Clone)]
+enum OrderingKey {
+    Chat,
+    User,
+    Custom(KeyFn),
+}
+
+/// Represents a way to order processing of updates.
+///
+/// Updates with the same key are handled one by one in the order they were received,
+/// while updates with different keys are handled concurrently.
+/// Updates without a key are handled without ordering.
+///
+/// Each key has a bounded queue; when the queue is full,
+/// receiving new updates waits until the handler of the key catches up,
+/// which delays updates of all other keys as well.
+///
+/// # Example
+///
+/// ```
+/// use tgbot::handler::{LongPollOptions, UpdateOrdering};
+///
+/// let options = LongPollOptions::default().with_ordering(UpdateOrdering::per_chat().with_queue_size(10));
+/// ```
+#[derive(Clone)]
+pub struct UpdateOrdering {
+    key: OrderingKey,
+    queue_size: usize,
+}
+
+impl UpdateOrdering {
+    fn new(key: OrderingKey) -> Self {
+        Self {
+            key,
+            queue_size: DEFAULT_QUEUE_SIZE,
+        }
+    }
+
+    /// Orders updates by [`Update::get_chat_id`].
+    pub fn per_chat() -> Self {
+        Self::new(OrderingKey::Chat)
+    }
+
+    /// Orders updates by [`Update::get_user_id`].
+    pub fn per_user() -> Self {
+        Self::new(OrderingKey::User)
+    }
+
+    /// Orders updates by a custom key.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A function which returns a key of an update, or `None` to skip ordering.
+    pub fn custom<F>(value: F) -> Self
+    where
+        F: Fn(&Update) -> Option<String> + Send + Sync + 'static,
+    {
+        Self::new(OrderingKey::Custom(Arc::new(value)))
+    }
+
+    /// Sets a new size of a queue of each key.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Maximum number of updates waiting to be handled per key; default - 100.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the value is 0.
+    pub fn with_queue_size(mut self, value: usize) -> Self {
+        assert!(value > 0, "Queue size must be greater than 0");
+        self.queue_size = value;
+        self
+    }
+
+    fn get_key(&self, update: &Update) -> Option<String> {
+        match &self.key {
+            OrderingKey::Chat => update.get_chat_id().map(|chat_id| format!("chat:{chat_id}")),
+            OrderingKey::User => update.get_user_id().map(|user_id| format!("user:{user_id}")),
+            OrderingKey::Custom(key) => key(update),
+        }
+    }
+}
+
+impl fmt::Debug for UpdateOrdering {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        let key = match self.key {
+            OrderingKey::Chat => "Chat",
+            OrderingKey::User => "User",
+            OrderingKey::Custom(_) => "Custom",
+        };
+        out.debug_struct("UpdateOrdering")
+            .field("key", &key)
+            .field("queue_size", &self.queue_size)
+            .finish()
+    }
+}
+
+impl PartialEq for UpdateOrdering {
+    fn eq(&self, other: &Self) -> bool {
+        let key = match (&self.key, &other.key) {
+            (OrderingKey::Chat, OrderingKey::Chat) | (OrderingKey::User, OrderingKey::User) => true,
+            (OrderingKey::Custom(a), OrderingKey::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        };
+        key && self.queue_size == other.queue_size
+    }
+}
+
+impl Eq for UpdateOrdering {}
+
+/// An update handler which processes updates in order according to [`UpdateOrdering`].
+///
+/// Updates are handled in spawned tasks,
+/// so [`UpdateHandler::handle`] returns as soon as an update is queued.
+/// A panic of the handler does not stop handling of the next updates with the same key.
+pub struct OrderedHandler<H> {
+    handler: Arc<H>,
+    ordering: UpdateOrdering,
+    semaphore: Option<Arc<Semaphore>>,
+    queues: Arc<Mutex<HashMap<String, Queue>>>,
+    closed: Arc<AtomicBool>,
+}
+
+struct Queue {
+    sender: Sender<Update>,
+    pending: usize,
+}
+
+impl<H> OrderedHandler<H> {
+    /// Creates a new `OrderedHandler`.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler of updates.
+    /// * `ordering` - The ordering of updates.
+    pub fn new(handler: H, ordering: UpdateOrdering) -> Self {
+        Self::from_arc(Arc::new(handler), ordering)
+    }
+
+    pub(crate) fn from_arc(handler: Arc<H>, ordering: UpdateOrdering) -> Self {
+        Self {
+            handler,
+            ordering,
+            semaphore: None,
+            queues: Default::default(),
+            closed: Default::default(),
+        }
+    }
+
+    pub(crate) fn with_semaphore(mut self, value: Arc<Semaphore>) -> Self {
+        self.semaphore = Some(value);
+        self
+    }
+
+    /// Drops updates waiting in queues; running handlers are not cancelled.
+    pub(crate) fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.queues.lock().unwrap().clear();
+    }
+}
+
+impl<H> UpdateHandler for OrderedHandler<H>
+where
+    H: UpdateHandler + Send + Sync + 'static,
+{
+    async fn handle(&self, update: Update) {
+        let Some(key) = self.ordering.get_key(&update) else {
+            let handler = self.handler.clone();
+            let permit = match &self.semaphore {
+                Some(semaphore) => Some(semaphore.clone().acquire_owned().await.unwrap()),
+                None => None,
+            };
+            spawn(async move {
+                let _permit = permit;
+                handler.handle(update).await;
+            });
+            return;
+        };
+        let sender = {
+            let mut queues = self.queues.lock().unwrap();
+            let queue = queues.entry(key.clone()).or_insert_with(|| {
+                let (sender, receiver) = channel(self.ordering.queue_size);
+                spawn(run_queue(
+                    key,
+                    receiver,
+                    self.handler.clone(),
+                    self.semaphore.clone(),
+                    self.queues.clone(),
+                    self.closed.clone(),
+                ));
+                Queue { sender, pending: 0 }
+            });
+            queue.pending += 1;
+            queue.sender.clone()
+        };
+        // The receiver is alive until there are no pending updates.
+        let _ = sender.send(update).await;
+    }
+}
+
+impl<H> fmt::Debug for OrderedHandler<H> {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        out.debug_struct("OrderedHandler")
+            .field("ordering", &self.ordering)
+            .field("queues", &self.queues.lock().unwrap().len())
+            .finish()
+    }
+}
+
+async fn run_queue<H>(
+    key: String,
+    mut receiver: Receiver<Update>,
+    handler: Arc<H>,
+    semaphore: Option<Arc<Semaphore>>,
+    queues: Arc<Mutex<HashMap<String, Queue>>>,
+    closed: Arc<AtomicBool>,
+) where
+    H: UpdateHandler + Send + Sync + 'static,
+{
+    while let Some(update) = receiver.recv().await {
+        {
+            let _permit = acquire(semaphore.as_ref()).await;
+            if closed.load(Ordering::Acquire) {
+                break;
+            }
+            // A separate task keeps the queue alive when the handler panics
+            let handler = handler.clone();
+            if let Err(err) = spawn(async move { handler.handle(update).await }).await {
+                error!("An error has occurred while handling an update with key {key}: {err}");
+            }
+        }
+        let mut queues = queues.lock().unwrap();
+        if let Some(queue) = queues.get_mut(&key) {
+            queue.pending -= 1;
+            if queue.pending == 0 {
+                queues.remove(&key);
+                break;
+            }
+        }
+    }
+}
+
+async fn acquire(semaphore: Option<&Arc<Semaphore>>) -> Option<SemaphorePermit<'_>> {
+    match semaphore {
+        Some(semaphore) => Some(semaphore.acquire().await.unwrap()),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+    use tokio::time::{Instant, sleep};
+
+    use super::*;
+
+    type Events = Arc<Mutex<Vec<(i64, &'static str)>>>;
+
+    struct Handler {
+        events: Events,
+    }
+
+    impl UpdateHandler for Handler {
+        async fn handle(&self, update: Update) {
+            self.events.lock().unwrap().push((update.id, "start"));
+            if update.id < 0 {
+                panic!("Unexpected update {}", update.id);
+            }
+            sleep(Duration::from_millis(10)).await;
+            self.events.lock().unwrap().push((update.id, "end"));
+        }
+    }
+
+    fn create_update(id: i64, chat_id: i64) -> Update {
+        serde_json::from_value(json!({
+            "update_id": id,
+            "message": {
+                "message_id": 1,
+                "date": 0,
+                "from": {"id": 1, "is_bot": false, "first_name": "John"},
+                "chat": {"id": chat_id, "type": "supergroup", "title": "Chat"},
+                "text": "text"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ordered_handler() {
+        let events = Events::default();
+        let handler = OrderedHandler::new(
+            Handler { events: events.clone() },
+            UpdateOrdering::per_chat().with_queue_size(1),
+        );
+        for (id, chat_id) in [(1, 1), (2, 2), (3, 1), (4, 1), (5, 2)] {
+            handler.handle(create_update(id, chat_id)).await;
+        }
+        sleep(Duration::from_secs(1)).await;
+        assert!(handler.queues.lock().unwrap().is_empty());
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 10);
+        let position = |id, event| events.iter().position(|x| *x == (id, event)).unwrap();
+        assert!(position(1, "end") < position(3, "start"));
+        assert!(position(3, "end") < position(4, "start"));
+        assert!(position(2, "end") < position(5, "start"));
+        assert!(position(2, "start") < position(1, "end"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ordered_handler_unkeyed() {
+        let events = Events::default();
+        let handler = OrderedHandler::new(Handler { events: events.clone() }, UpdateOrdering::custom(|_| None))
+            .with_semaphore(Arc::new(Semaphore::new(1)));
+        let now = Instant::now();
+        handler.handle(create_update(1, 1)).await;
+        assert!(now.elapsed() < Duration::from_millis(10));
+        handler.handle(create_update(2, 1)).await;
+        assert!(now.elapsed() >= Duration::from_millis(10));
+        sleep(Duration::from_secs(1)).await;
+        assert_eq!(events.lock().unwrap().len(), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ordered_handler_panic() {
+        let events = Events::default();
+        let handler = OrderedHandler::new(Handler { events: events.clone() }, UpdateOrdering::per_chat());
+        for id in [1, -2, 3] {
+            handler.handle(create_update(id, 1)).await;
+        }
+        sleep(Duration::from_secs(1)).await;
+        assert!(handler.queues.lock().unwrap().is_empty());
+        assert_eq!(
+            *events.lock().unwrap(),
+            [(1, "start"), (1, "end"), (-2, "start"), (3, "start"), (3, "end")]
+        );
+
+        handler.handle(create_update(4, 1)).await;
+        sleep(Duration::from_secs(1)).await;
+        assert_eq!(events.lock().unwrap().last(), Some(&(4, "end")));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ordered_handler_close() {
+        let events = Events::default();
+        let handler = OrderedHandler::new(Handler { events: events.clone() }, UpdateOrdering::per_chat());
+        for id in 1..=3 {
+            handler.handle(create_update(id, 1)).await;
+        }
+        sleep(Duration::from_millis(5)).await;
+        handler.close();
+        sleep(Duration::from_secs(1)).await;
+        assert!(handler.queues.lock().unwrap().is_empty());
+        assert_eq!(*events.lock().unwrap(), [(1, "start"), (1, "end")]);
+    }
+
+    #[test]
+    fn update_ordering() {
+        let update = create_update(1, 2);
+        assert_eq!(UpdateOrdering::per_chat().get_key(&update).unwrap(), "chat:2");
+        assert_eq!(UpdateOrdering::per_user().get_key(&update).unwrap(), "user:1");
+        let ordering = UpdateOrdering::custom(|update| Some(update.id.to_string()));
+        assert_eq!(ordering.get_key(&update).unwrap(), "1");
+        assert_eq!(ordering, ordering.clone());
+        assert_ne!(ordering, UpdateOrdering::custom(|_| None));
+        assert_ne!(UpdateOrdering::per_chat(), UpdateOrdering::per_user());
+        assert_ne!(
+            UpdateOrdering::per_chat(),
+            UpdateOrdering::per_chat().with_queue_size(1)
+        );
+    }
+}
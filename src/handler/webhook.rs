@@ -3,7 +3,10 @@ use std::{io::Error as IoError, net::SocketAddr, sync::Arc};
 use axum::Router;
 use tokio::net::TcpListener;
 
-use crate::{handler::UpdateHandler, types::Update};
+use crate::{
+    handler::{OrderedHandler, UpdateHandler, UpdateOrdering},
+    types::Update,
+};
 
 /// Represents a simple webhook server for handling incoming updates from the Telegram Bot API.
 #[cfg_attr(nightly, doc(cfg(feature = "webhook")))]
@@ -29,6 +32,25 @@ impl WebhookServer {
         Self { router }
     }
 
+    /// Creates a new `WebhookServer` which processes updates in order.
+    ///
+    /// The server responds as soon as an update is queued,
+    /// see [`OrderedHandler`] for details;
+    /// when the queue of a key is full, the response waits until the queue has room.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path where the webhook server will receive incoming updates.
+    /// * `handler` - The handler for processing updates.
+    /// * `ordering` - The ordering of updates.
+    pub fn new_ordered<A, B>(path: A, handler: B, ordering: UpdateOrdering) -> Self
+    where
+        A: AsRef<str>,
+        B: UpdateHandler + Send + Sync + 'static,
+    {
+        Self::new(path, OrderedHandler::new(handler, ordering))
+    }
+
     /// Runs the server
     ///
     /// Returns the local address that the server is bound to.
@@ -251,8 +251,8 @@ impl BotCommand {
     {
         let name = name.into();
         let description = description.into();
-        let name_len = name.len();
-        let description_len = description.len();
+        let name_len = name.chars().count();
+        let description_len = description.chars().count();
         if !(Self::MIN_NAME_LEN..=Self::MAX_NAME_LEN).contains(&name_len) {
             Err(BotCommandError::BadNameLen(name_len))
         } else if !(Self::MIN_DESCRIPTION_LEN..=Self::MAX_DESCRIPTION_LEN).contains(&description_len) {
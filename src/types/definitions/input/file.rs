@@ -1,4 +1,8 @@
-use std::{fmt, path::Path};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use mime::{APPLICATION_OCTET_STREAM, Mime};
 use tokio::{
@@ -9,10 +13,37 @@ use tokio_util::codec::{BytesCodec, FramedRead};
 
 use crate::api::FormValue;
 
+pub(crate) type UploadProgressHandler = Arc<dyn Fn(UploadProgress) + Send + Sync>;
+
+/// Represents a progress of a file upload.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct UploadProgress {
+    sent: u64,
+    total: Option<u64>,
+}
+
+impl UploadProgress {
+    pub(crate) fn new(sent: u64, total: Option<u64>) -> Self {
+        Self { sent, total }
+    }
+
+    /// Returns the number of bytes sent.
+    pub fn sent(&self) -> u64 {
+        self.sent
+    }
+
+    /// Returns the total number of bytes to send, if known.
+    pub fn total(&self) -> Option<u64> {
+        self.total
+    }
+}
+
 /// Represents a file reader for uploading files.
 pub struct InputFileReader {
     file_name: Option<String>,
+    file_size: Option<u64>,
     mime_type: Option<Mime>,
+    progress_handler: Option<UploadProgressHandler>,
     reader: FramedRead<Box<dyn AsyncRead + Send + Sync + Unpin>, BytesCodec>,
 }
 
@@ -29,7 +60,9 @@ impl InputFileReader {
         InputFileReader {
             reader: FramedRead::new(Box::new(reader), BytesCodec::new()),
             file_name: None,
+            file_size: None,
             mime_type: None,
+            progress_handler: None,
         }
     }
 
@@ -65,6 +98,54 @@ impl InputFileReader {
     pub fn mime_type(&self) -> Option<&Mime> {
         self.mime_type.as_ref()
     }
+
+    /// Sets a new size of the file.
+    ///
+    /// The size is reported as [`UploadProgress::total`].
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The size of the file in bytes.
+    pub fn with_file_size(mut self, value: u64) -> Self {
+        self.file_size = Some(value);
+        self
+    }
+
+    /// Returns the size of the file.
+    pub fn file_size(&self) -> Option<u64> {
+        self.file_size
+    }
+
+    /// Sets a new upload progress handler.
+    ///
+    /// The handler is called each time a chunk of the file is sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The handler.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    ///
+    /// use tgbot::types::{InputFileReader, UploadProgress};
+    /// use tokio::sync::watch;
+    ///
+    /// let (tx, rx) = watch::channel(UploadProgress::default());
+    /// let reader = InputFileReader::from(Cursor::new(b"data"))
+    ///     .with_file_size(4)
+    ///     .with_progress_handler(move |progress| {
+    ///         tx.send_replace(progress);
+    ///     });
+    /// ```
+    pub fn with_progress_handler<T>(mut self, value: T) -> Self
+    where
+        T: Fn(UploadProgress) + Send + Sync + 'static,
+    {
+        self.progress_handler = Some(Arc::new(value));
+        self
+    }
 }
 
 impl<T> From<T> for InputFileReader
@@ -91,6 +172,116 @@ impl fmt::Debug for InputFileReader {
     }
 }
 
+/// Represents a file on a filesystem for uploading files.
+///
+/// The file is opened when a request is sent.
+#[derive(Clone)]
+pub struct InputFilePath {
+    path: PathBuf,
+    file_name: Option<String>,
+    mime_type: Option<Mime>,
+    progress_handler: Option<UploadProgressHandler>,
+}
+
+impl InputFilePath {
+    /// Creates a new `InputFilePath`.
+    ///
+    /// The name and the MIME type of the file are detected from the path.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Path to file on a filesystem.
+    pub fn new<T>(value: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        let path = value.into();
+        let file_name = path.file_name().and_then(|x| x.to_str()).map(String::from);
+        let mime_type = file_name.as_ref().map(|_| {
+            path.extension()
+                .and_then(|x| x.to_str())
+                .and_then(|x| mime_guess::from_ext(x).first())
+                .unwrap_or(APPLICATION_OCTET_STREAM)
+        });
+        Self {
+            path,
+            file_name,
+            mime_type,
+            progress_handler: None,
+        }
+    }
+
+    /// Returns the path to the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Sets a new name of the file
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The name of the file.
+    pub fn with_file_name<T>(mut self, value: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.file_name = Some(value.into());
+        self
+    }
+
+    /// Returns the name of the file.
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// Sets a new MIME type of the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The MIME type of the file.
+    pub fn with_mime_type(mut self, value: Mime) -> Self {
+        self.mime_type = Some(value);
+        self
+    }
+
+    /// Returns the MIME type of the file.
+    pub fn mime_type(&self) -> Option<&Mime> {
+        self.mime_type.as_ref()
+    }
+
+    /// Sets a new upload progress handler.
+    ///
+    /// The handler is called each time a chunk of the file is sent;
+    /// the total number of bytes is taken from the file metadata.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The handler.
+    pub fn with_progress_handler<T>(mut self, value: T) -> Self
+    where
+        T: Fn(UploadProgress) + Send + Sync + 'static,
+    {
+        self.progress_handler = Some(Arc::new(value));
+        self
+    }
+}
+
+impl PartialEq for InputFilePath {
+    fn eq(&self, other: &Self) -> bool {
+        self.path.eq(&other.path) && self.file_name.eq(&other.file_name) && self.mime_type.eq(&other.mime_type)
+    }
+}
+
+impl fmt::Debug for InputFilePath {
+    fn fmt(&self, out: &mut fmt::Formatter<'_>) -> fmt::Result {
+        out.debug_struct("InputFilePath")
+            .field("path", &self.path)
+            .field("file_name", &self.file_name)
+            .field("mime_type", &self.mime_type)
+            .finish()
+    }
+}
+
 /// Represents a file to upload.
 #[derive(Debug, PartialEq)]
 pub enum InputFile {
@@ -100,6 +291,8 @@ pub enum InputFile {
     Url(String),
     /// A file to upload using `multipart/form-data`.
     Reader(InputFileReader),
+    /// A file on a filesystem to upload using `multipart/form-data`.
+    Path(InputFilePath),
 }
 
 impl InputFile {
@@ -129,22 +322,30 @@ impl InputFile {
 
     /// Creates an `InputFile` from a file path.
     ///
+    /// The file is opened each time a request is sent,
+    /// so requests containing the file can be retried.
+    ///
+    /// When the client works with a local Bot API server,
+    /// the file is sent as a `file://` URI instead of uploading;
+    /// see [`crate::api::Client::with_local_mode`].
+    ///
     /// # Arguments
     ///
     /// * `value` - Path to file on a filesystem.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the file does not exist.
     pub async fn path(path: impl AsRef<Path>) -> IoResult<Self> {
         let path = path.as_ref();
-        let file = fs::File::open(path).await?;
-        let mut reader = InputFileReader::new(file);
-        if let Some(file_name) = path.file_name().and_then(|x| x.to_str()) {
-            let mime_type = path
-                .extension()
-                .and_then(|x| x.to_str())
-                .and_then(|x| mime_guess::from_ext(x).first())
-                .unwrap_or(APPLICATION_OCTET_STREAM);
-            reader = reader.with_file_name(file_name).with_mime_type(mime_type);
-        }
-        Ok(reader.into())
+        fs::metadata(path).await?;
+        Ok(InputFilePath::new(path).into())
+    }
+}
+
+impl From<InputFilePath> for InputFile {
+    fn from(value: InputFilePath) -> Self {
+        InputFile::Path(value)
     }
 }
 
@@ -163,13 +364,28 @@ impl From<InputFile> for FormValue {
             InputFile::Id(value) | InputFile::Url(value) => FormValue::Text(value),
             InputFile::Reader(InputFileReader {
                 file_name: name,
+                file_size,
                 mime_type,
+                progress_handler,
                 reader,
             }) => FormValue::File {
                 name,
+                size: file_size,
                 mime_type,
+                progress_handler,
                 reader,
             },
+            InputFile::Path(InputFilePath {
+                path,
+                file_name: name,
+                mime_type,
+                progress_handler,
+            }) => FormValue::Path {
+                name,
+                mime_type,
+                path,
+                progress_handler,
+            },
         }
     }
 }
@@ -1,8 +1,11 @@
 use std::{convert::TryFrom, error::Error, fmt, string::FromUtf16Error};
 
 use shellwords::MismatchedQuotes;
+#[cfg(feature = "macros")]
+#[cfg_attr(nightly, doc(cfg(feature = "macros")))]
+pub use tgbot_macros::BotCommands;
 
-use crate::types::Message;
+use crate::types::{BotCommand, Message};
 
 /// A simple command implementation.
 ///
@@ -117,3 +120,89 @@ impl TryFrom<Message> for Command {
         }
     }
 }
+
+/// Represents a set of commands with typed arguments.
+///
+/// Use `#[derive(BotCommands)]` to implement the trait for an enum (requires the `macros` feature).
+pub trait BotCommands: Sized {
+    /// Parses a command.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The command to parse.
+    fn from_command(command: &Command) -> Result<Self, ParseCommandError>;
+
+    /// Returns a list of commands for [`crate::types::SetBotCommands`].
+    fn bot_commands() -> Vec<BotCommand>;
+
+    /// Parses a command from a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message containing the command.
+    fn from_message(message: Message) -> Result<Self, ParseCommandError> {
+        let command = Command::try_from(message)?;
+        Self::from_command(&command)
+    }
+}
+
+/// Represents an error when parsing a command with typed arguments.
+#[derive(Debug)]
+pub enum ParseCommandError {
+    /// Failed to get a command from a message.
+    Command(CommandError),
+    /// A value of an argument can not be parsed.
+    InvalidArgument {
+        /// The name of the argument.
+        name: &'static str,
+        /// The value of the argument.
+        value: String,
+        /// The parsing error.
+        error: String,
+    },
+    /// A required argument is missing.
+    MissingArgument {
+        /// The name of the argument.
+        name: &'static str,
+    },
+    /// The command has more arguments than expected.
+    TooManyArguments {
+        /// The expected number of arguments.
+        expected: usize,
+        /// The actual number of arguments.
+        actual: usize,
+    },
+    /// The command is unknown; contains the name of the command with leading slash.
+    UnknownCommand(String),
+}
+
+impl From<CommandError> for ParseCommandError {
+    fn from(err: CommandError) -> Self {
+        Self::Command(err)
+    }
+}
+
+impl Error for ParseCommandError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseCommandError::Command(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ParseCommandError {
+    fn fmt(&self, out: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseCommandError::Command(err) => write!(out, "{err}"),
+            ParseCommandError::InvalidArgument { name, value, error } => {
+                write!(out, "invalid argument {name}={value}: {error}")
+            }
+            ParseCommandError::MissingArgument { name } => write!(out, "missing argument {name}"),
+            ParseCommandError::TooManyArguments { expected, actual } => {
+                write!(out, "too many arguments: expected={expected} actual={actual}")
+            }
+            ParseCommandError::UnknownCommand(name) => write!(out, "unknown command {name}"),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::{error::Error, fmt};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize, Serializer};
 
 use crate::types::Integer;
 
@@ -32,18 +32,47 @@ impl<T> Response<T> {
     }
 }
 
+impl<T> Serialize for Response<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let raw = match self {
+            Response::Success(result) => RawResponse {
+                ok: true,
+                result: Some(result),
+                description: None,
+                error_code: None,
+                parameters: None,
+            },
+            Response::Error(err) => RawResponse {
+                ok: false,
+                result: None,
+                description: Some(err.description.clone()),
+                error_code: err.error_code,
+                parameters: match (err.migrate_to_chat_id, err.retry_after) {
+                    (None, None) => None,
+                    (migrate_to_chat_id, retry_after) => Some(RawResponseParameters {
+                        migrate_to_chat_id,
+                        retry_after,
+                    }),
+                },
+            },
+        };
+        raw.serialize(serializer)
+    }
+}
+
 impl<T> From<RawResponse<T>> for Response<T> {
     fn from(raw: RawResponse<T>) -> Self {
         if raw.ok {
             if let Some(result) = raw.result {
                 Response::Success(result)
             } else {
-                Response::Error(ResponseError {
-                    description: String::from("response is ok, but result is not provided"),
-                    error_code: None,
-                    migrate_to_chat_id: None,
-                    retry_after: None,
-                })
+                Response::Error(ResponseError::new("response is ok, but result is not provided"))
             }
         } else {
             Response::Error(ResponseError {
@@ -66,6 +95,53 @@ pub struct ResponseError {
 }
 
 impl ResponseError {
+    /// Creates a new `ResponseError`.
+    ///
+    /// # Arguments
+    ///
+    /// * `description` - A human-readable description of the error.
+    pub fn new<T>(description: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            description: description.into(),
+            error_code: None,
+            migrate_to_chat_id: None,
+            retry_after: None,
+        }
+    }
+
+    /// Sets a new error code.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The error code.
+    pub fn with_error_code(mut self, value: Integer) -> Self {
+        self.error_code = Some(value);
+        self
+    }
+
+    /// Sets a new identifier of a supergroup the group has been migrated to.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The identifier of the supergroup.
+    pub fn with_migrate_to_chat_id(mut self, value: Integer) -> Self {
+        self.migrate_to_chat_id = Some(value);
+        self
+    }
+
+    /// Sets a new number of seconds left to wait before the request can be repeated.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The number of seconds.
+    pub fn with_retry_after(mut self, value: Integer) -> Self {
+        self.retry_after = Some(value);
+        self
+    }
+
     /// Returns a human-readable description of the error.
     pub fn description(&self) -> &str {
         &self.description
@@ -90,6 +166,98 @@ impl ResponseError {
     pub fn migrate_to_chat_id(&self) -> Option<Integer> {
         self.migrate_to_chat_id
     }
+
+    /// Returns a kind of the error.
+    ///
+    /// The kind is derived from the error code, parameters and description of the error.
+    pub fn kind(&self) -> ResponseErrorKind {
+        use self::ResponseErrorKind::*;
+        if self.retry_after.is_some() || self.error_code == Some(429) {
+            return TooManyRequests;
+        }
+        if self.migrate_to_chat_id.is_some() {
+            return ChatMigrated;
+        }
+        match self.error_code {
+            Some(401) => return Unauthorized,
+            Some(409) => return Conflict,
+            Some(code) if code >= 500 => return ServerError,
+            _ => {}
+        }
+        let description = self.description.to_lowercase();
+        const PATTERNS: [(&str, ResponseErrorKind); 17] = [
+            ("bot was blocked by the user", BotBlocked),
+            ("bot was kicked", BotKicked),
+            ("bot is not a member", BotKicked),
+            ("user is deactivated", UserDeactivated),
+            ("bot can't initiate conversation", CantInitiateConversation),
+            ("chat not found", ChatNotFound),
+            ("user not found", UserNotFound),
+            ("message is not modified", MessageNotModified),
+            ("message to edit not found", MessageToEditNotFound),
+            ("message to delete not found", MessageToDeleteNotFound),
+            ("message can't be edited", MessageCantBeEdited),
+            ("message can't be deleted", MessageCantBeDeleted),
+            ("message to be replied not found", ReplyMessageNotFound),
+            ("query is too old", QueryTooOld),
+            ("not enough rights", NotEnoughRights),
+            ("wrong file identifier", InvalidFileId),
+            ("wrong remote file identifier", InvalidFileId),
+        ];
+        PATTERNS
+            .into_iter()
+            .find_map(|(pattern, kind)| description.contains(pattern).then_some(kind))
+            .unwrap_or(Other)
+    }
+}
+
+/// Represents a kind of a response error.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ResponseErrorKind {
+    /// The bot was blocked by the user.
+    BotBlocked,
+    /// The bot was kicked from the chat or is not a member of the chat.
+    BotKicked,
+    /// The bot can not initiate a conversation with the user.
+    CantInitiateConversation,
+    /// The group has been migrated to a supergroup;
+    /// see [`ResponseError::migrate_to_chat_id`].
+    ChatMigrated,
+    /// The chat not found.
+    ChatNotFound,
+    /// A conflicting request is running, e.g. another `getUpdates` request or a webhook is active.
+    Conflict,
+    /// The file identifier is invalid or can not be used by the bot.
+    InvalidFileId,
+    /// The message can not be deleted.
+    MessageCantBeDeleted,
+    /// The message can not be edited.
+    MessageCantBeEdited,
+    /// The new content of the message is the same as the current one.
+    MessageNotModified,
+    /// The message to delete not found.
+    MessageToDeleteNotFound,
+    /// The message to edit not found.
+    MessageToEditNotFound,
+    /// The bot has not enough rights in the chat.
+    NotEnoughRights,
+    /// The query is too old or the query ID is invalid.
+    QueryTooOld,
+    /// The message to reply to not found.
+    ReplyMessageNotFound,
+    /// An internal error of the Bot API server.
+    ServerError,
+    /// Flood control exceeded; see [`ResponseError::retry_after`].
+    TooManyRequests,
+    /// The bot token is invalid.
+    Unauthorized,
+    /// The user is deactivated.
+    UserDeactivated,
+    /// The user not found.
+    UserNotFound,
+    /// Any other error.
+    Other,
 }
 
 impl Error for ResponseError {}
@@ -110,7 +278,8 @@ impl fmt::Display for ResponseError {
     }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[serde_with::skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 struct RawResponse<T> {
     ok: bool,
     result: Option<T>,
@@ -119,7 +288,8 @@ struct RawResponse<T> {
     parameters: Option<RawResponseParameters>,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde_with::skip_serializing_none]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
 struct RawResponseParameters {
     migrate_to_chat_id: Option<Integer>,
     retry_after: Option<Integer>,
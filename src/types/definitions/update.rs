@@ -269,167 +269,62 @@ impl From<UnexpectedUpdate> for Update {
     }
 }
 
-impl TryFrom<Update> for BusinessConnection {
-    type Error = UnexpectedUpdate;
-
-    fn try_from(value: Update) -> Result<Self, Self::Error> {
-        use self::UpdateType::*;
-        match value.update_type {
-            BusinessConnection(x) => Ok(*x),
-            _ => Err(UnexpectedUpdate(value)),
-        }
-    }
-}
-
-impl TryFrom<Update> for BusinessMessagesDeleted {
-    type Error = UnexpectedUpdate;
-
-    fn try_from(value: Update) -> Result<Self, Self::Error> {
-        use self::UpdateType::*;
-        match value.update_type {
-            DeletedBusinessMessages(x) => Ok(*x),
-            _ => Err(UnexpectedUpdate(value)),
-        }
-    }
-}
-
-impl TryFrom<Update> for ChatMemberUpdated {
-    type Error = UnexpectedUpdate;
-
-    fn try_from(value: Update) -> Result<Self, Self::Error> {
-        use self::UpdateType::*;
-        match value.update_type {
-            BotStatus(x) | UserStatus(x) => Ok(*x),
-            _ => Err(UnexpectedUpdate(value)),
-        }
-    }
-}
-
-impl TryFrom<Update> for CallbackQuery {
-    type Error = UnexpectedUpdate;
-
-    fn try_from(value: Update) -> Result<Self, Self::Error> {
-        use self::UpdateType::*;
-        match value.update_type {
-            CallbackQuery(x) => Ok(*x),
-            _ => Err(UnexpectedUpdate(value)),
-        }
-    }
-}
-
-impl TryFrom<Update> for ChatJoinRequest {
-    type Error = UnexpectedUpdate;
-
-    fn try_from(value: Update) -> Result<Self, Self::Error> {
-        use self::UpdateType::*;
-        match value.update_type {
-            ChatJoinRequest(x) => Ok(*x),
-            _ => Err(UnexpectedUpdate(value)),
-        }
-    }
-}
-
-impl TryFrom<Update> for ChosenInlineResult {
-    type Error = UnexpectedUpdate;
-
-    fn try_from(value: Update) -> Result<Self, Self::Error> {
-        use self::UpdateType::*;
-        match value.update_type {
-            ChosenInlineResult(x) => Ok(*x),
-            _ => Err(UnexpectedUpdate(value)),
-        }
-    }
-}
-
-impl TryFrom<Update> for InlineQuery {
-    type Error = UnexpectedUpdate;
-
-    fn try_from(value: Update) -> Result<Self, Self::Error> {
-        use self::UpdateType::*;
-        match value.update_type {
-            InlineQuery(x) => Ok(*x),
-            _ => Err(UnexpectedUpdate(value)),
-        }
-    }
-}
-
-impl TryFrom<Update> for Message {
-    type Error = UnexpectedUpdate;
-
-    fn try_from(value: Update) -> Result<Self, Self::Error> {
-        use self::UpdateType::*;
-        match value.update_type {
-            BusinessMessage(x)
-            | EditedBusinessMessage(x)
-            | EditedChannelPost(x)
-            | EditedMessage(x)
-            | GuestMessage(x)
-            | ChannelPost(x)
-            | Message(x) => Ok(*x),
-            _ => Err(UnexpectedUpdate(value)),
-        }
-    }
-}
-
-impl TryFrom<Update> for Poll {
-    type Error = UnexpectedUpdate;
-
-    fn try_from(value: Update) -> Result<Self, Self::Error> {
-        use self::UpdateType::*;
-        match value.update_type {
-            Poll(x) => Ok(*x),
-            _ => Err(UnexpectedUpdate(value)),
-        }
-    }
-}
-
-impl TryFrom<Update> for PollAnswer {
-    type Error = UnexpectedUpdate;
-
-    fn try_from(value: Update) -> Result<Self, Self::Error> {
-        use self::UpdateType::*;
-        match value.update_type {
-            PollAnswer(x) => Ok(*x),
-            _ => Err(UnexpectedUpdate(value)),
-        }
-    }
-}
-
-impl TryFrom<Update> for PreCheckoutQuery {
-    type Error = UnexpectedUpdate;
-
-    fn try_from(value: Update) -> Result<Self, Self::Error> {
-        use self::UpdateType::*;
-        match value.update_type {
-            PreCheckoutQuery(x) => Ok(*x),
-            _ => Err(UnexpectedUpdate(value)),
-        }
-    }
+/// Represents a type which can be extracted from an update using `TryFrom<Update>`.
+///
+/// Used by [`crate::handler::Filter::kind`] to check the type of an update without converting it.
+pub trait UpdateKind: TryFrom<Update, Error = UnexpectedUpdate> {
+    /// Returns whether an update of the given type is convertible to `Self`.
+    ///
+    /// # Arguments
+    ///
+    /// * `update_type` - The type of the update.
+    fn matches(update_type: &UpdateType) -> bool;
 }
 
-impl TryFrom<Update> for PaidMediaPurchased {
-    type Error = UnexpectedUpdate;
+macro_rules! impl_update_kind {
+    ($($kind:ident => $($variant:ident)|+),+ $(,)?) => {
+        $(
+            impl TryFrom<Update> for $kind {
+                type Error = UnexpectedUpdate;
+
+                fn try_from(value: Update) -> Result<Self, Self::Error> {
+                    match value.update_type {
+                        $(UpdateType::$variant(x))|+ => Ok(*x),
+                        _ => Err(UnexpectedUpdate(value)),
+                    }
+                }
+            }
 
-    fn try_from(value: Update) -> Result<Self, Self::Error> {
-        use self::UpdateType::*;
-        match value.update_type {
-            PurchasedPaidMedia(x) => Ok(*x),
-            _ => Err(UnexpectedUpdate(value)),
-        }
-    }
+            impl UpdateKind for $kind {
+                fn matches(update_type: &UpdateType) -> bool {
+                    matches!(update_type, $(UpdateType::$variant(_))|+)
+                }
+            }
+        )+
+    };
 }
 
-impl TryFrom<Update> for ShippingQuery {
-    type Error = UnexpectedUpdate;
-
-    fn try_from(value: Update) -> Result<Self, Self::Error> {
-        use self::UpdateType::*;
-        match value.update_type {
-            ShippingQuery(x) => Ok(*x),
-            _ => Err(UnexpectedUpdate(value)),
-        }
-    }
-}
+impl_update_kind!(
+    BusinessConnection => BusinessConnection,
+    BusinessMessagesDeleted => DeletedBusinessMessages,
+    ChatMemberUpdated => BotStatus | UserStatus,
+    CallbackQuery => CallbackQuery,
+    ChatJoinRequest => ChatJoinRequest,
+    ChosenInlineResult => ChosenInlineResult,
+    InlineQuery => InlineQuery,
+    Message => BusinessMessage
+        | EditedBusinessMessage
+        | EditedChannelPost
+        | EditedMessage
+        | GuestMessage
+        | ChannelPost
+        | Message,
+    Poll => Poll,
+    PollAnswer => PollAnswer,
+    PreCheckoutQuery => PreCheckoutQuery,
+    PaidMediaPurchased => PurchasedPaidMedia,
+    ShippingQuery => ShippingQuery,
+);
 
 /// Represents a type of update to receive.
 #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
@@ -43,6 +43,8 @@ fn bot_command() {
         "command description can have a length of 3 up to 256 characters, got 257"
     );
 
+    assert!(BotCommand::new("name", "д".repeat(256)).is_ok());
+
     let bot_command = BotCommand::new("name", "description").unwrap();
     assert_eq!(bot_command.name(), "name");
     assert_eq!(bot_command.description(), "description");
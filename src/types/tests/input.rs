@@ -17,7 +17,21 @@ async fn input_file() {
     let path = InputFile::path("LICENSE").await.unwrap();
     assert_eq!(
         format!("{path:?}"),
-        r#"Reader(InputFileReader { file_name: Some("LICENSE"), mime_type: Some("application/octet-stream") })"#,
+        r#"Path(InputFilePath { path: "LICENSE", file_name: Some("LICENSE"), mime_type: Some("application/octet-stream") })"#,
+    );
+    assert!(InputFile::path("not-found").await.is_err());
+
+    let path = InputFilePath::new("archive.zip");
+    assert_eq!(path.path(), std::path::Path::new("archive.zip"));
+    assert_eq!(path.file_name().unwrap(), "archive.zip");
+    assert_eq!(path.mime_type().unwrap().as_ref(), "application/zip");
+    let path = path.with_file_name("name").with_mime_type(mime::TEXT_PLAIN);
+    assert_eq!(path.file_name().unwrap(), "name");
+    assert_eq!(path.mime_type().unwrap(), &mime::TEXT_PLAIN);
+    let path = InputFile::from(path);
+    assert_eq!(
+        format!("{path:?}"),
+        r#"Path(InputFilePath { path: "archive.zip", file_name: Some("name"), mime_type: Some("text/plain") })"#,
     );
 
     let reader = InputFileReader::from(Cursor::new(b"data"))
@@ -25,6 +39,9 @@ async fn input_file() {
         .with_mime_type(mime::TEXT_PLAIN);
     assert_eq!(reader.file_name().unwrap(), "name");
     assert_eq!(reader.mime_type().unwrap(), &mime::TEXT_PLAIN);
+    assert!(reader.file_size().is_none());
+    let reader = reader.with_file_size(4).with_progress_handler(|_| {});
+    assert_eq!(reader.file_size().unwrap(), 4);
     let reader = InputFile::from(reader);
     assert_eq!(
         format!("{reader:?}"),
@@ -39,8 +39,7 @@ macro_rules! assert_payload_eq {
         assert_eq!($expected_http_method, payload.http_method);
         match ($expected_data, payload.payload_data) {
             (crate::types::tests::ExpectedData::Json, crate::api::PayloadData::Json(actual_result)) => {
-                let actual_data_raw = actual_result.unwrap();
-                let actual_data: serde_json::Value = serde_json::from_str(&actual_data_raw).unwrap();
+                let actual_data: serde_json::Value = actual_result.unwrap();
                 insta::assert_json_snapshot!(actual_data);
             }
             (crate::types::tests::ExpectedData::Form, crate::api::PayloadData::Form(actual_form)) => {
@@ -56,3 +56,146 @@ fn deserialize() {
         panic!("Unexpected response: {success:?}");
     }
 }
+
+#[test]
+fn serialize() {
+    let success: Response<bool> = Response::Success(true);
+    assert_eq!(
+        serde_json::to_value(success).unwrap(),
+        serde_json::json!({"ok": true, "result": true})
+    );
+
+    let error: Response<bool> = Response::Error(ResponseError::new("test err").with_error_code(1).with_retry_after(3));
+    assert_eq!(
+        serde_json::to_value(error).unwrap(),
+        serde_json::json!({
+            "ok": false,
+            "description": "test err",
+            "error_code": 1,
+            "parameters": {"retry_after": 3}
+        })
+    );
+}
+
+#[test]
+fn response_error() {
+    let err = ResponseError::new("test err")
+        .with_error_code(400)
+        .with_migrate_to_chat_id(2)
+        .with_retry_after(3);
+    assert_eq!(err.description(), "test err");
+    assert_eq!(err.error_code(), Some(400));
+    assert_eq!(err.migrate_to_chat_id(), Some(2));
+    assert_eq!(err.retry_after(), Some(3));
+    assert_eq!(
+        err.to_string(),
+        "a telegram error has occurred: description=test err; error_code=400; migrate_to_chat_id=2; retry_after=3"
+    );
+}
+
+#[test]
+fn response_error_kind() {
+    for (error_code, description, expected) in [
+        (
+            403,
+            "Forbidden: bot was blocked by the user",
+            ResponseErrorKind::BotBlocked,
+        ),
+        (
+            403,
+            "Forbidden: bot was kicked from the group chat",
+            ResponseErrorKind::BotKicked,
+        ),
+        (
+            403,
+            "Forbidden: bot is not a member of the channel chat",
+            ResponseErrorKind::BotKicked,
+        ),
+        (
+            403,
+            "Forbidden: bot can't initiate conversation with a user",
+            ResponseErrorKind::CantInitiateConversation,
+        ),
+        (
+            403,
+            "Forbidden: user is deactivated",
+            ResponseErrorKind::UserDeactivated,
+        ),
+        (400, "Bad Request: chat not found", ResponseErrorKind::ChatNotFound),
+        (400, "Bad Request: user not found", ResponseErrorKind::UserNotFound),
+        (
+            400,
+            "Bad Request: message is not modified: specified new message content and reply markup are exactly the same",
+            ResponseErrorKind::MessageNotModified,
+        ),
+        (
+            400,
+            "Bad Request: message to edit not found",
+            ResponseErrorKind::MessageToEditNotFound,
+        ),
+        (
+            400,
+            "Bad Request: message to delete not found",
+            ResponseErrorKind::MessageToDeleteNotFound,
+        ),
+        (
+            400,
+            "Bad Request: message can't be edited",
+            ResponseErrorKind::MessageCantBeEdited,
+        ),
+        (
+            400,
+            "Bad Request: message can't be deleted",
+            ResponseErrorKind::MessageCantBeDeleted,
+        ),
+        (
+            400,
+            "Bad Request: message to be replied not found",
+            ResponseErrorKind::ReplyMessageNotFound,
+        ),
+        (
+            400,
+            "Bad Request: query is too old and response timeout expired or query ID is invalid",
+            ResponseErrorKind::QueryTooOld,
+        ),
+        (
+            400,
+            "Bad Request: not enough rights to send text messages to the chat",
+            ResponseErrorKind::NotEnoughRights,
+        ),
+        (
+            400,
+            "Bad Request: wrong file identifier/HTTP URL specified",
+            ResponseErrorKind::InvalidFileId,
+        ),
+        (
+            400,
+            "Bad Request: wrong remote file identifier specified: Wrong padding length",
+            ResponseErrorKind::InvalidFileId,
+        ),
+        (401, "Unauthorized", ResponseErrorKind::Unauthorized),
+        (
+            409,
+            "Conflict: terminated by other getUpdates request",
+            ResponseErrorKind::Conflict,
+        ),
+        (502, "Bad Gateway", ResponseErrorKind::ServerError),
+        (
+            429,
+            "Too Many Requests: retry after 5",
+            ResponseErrorKind::TooManyRequests,
+        ),
+        (400, "Bad Request: unknown", ResponseErrorKind::Other),
+    ] {
+        let err = ResponseError::new(description).with_error_code(error_code);
+        assert_eq!(err.kind(), expected, "{description}");
+    }
+
+    let err = ResponseError::new("Bad Request: group chat was upgraded to a supergroup chat")
+        .with_error_code(400)
+        .with_migrate_to_chat_id(1);
+    assert_eq!(err.kind(), ResponseErrorKind::ChatMigrated);
+
+    let err = ResponseError::new("Too Many Requests").with_retry_after(1);
+    assert_eq!(err.kind(), ResponseErrorKind::TooManyRequests);
+}
@@ -0,0 +1,133 @@
+#![allow(missing_docs)]
+use std::time::Duration;
+
+use mockito::Server;
+use tgbot::{
+    api::{Client as AsyncClient, DownloadOptions, ExecuteError, ExecuteOptions, ExponentialBackoff, blocking::Client},
+    types::Close,
+};
+
+#[test]
+fn execute() {
+    let mut server = Server::new();
+    let error_mock = server
+        .mock("GET", "/bot-token/close")
+        .with_status(502)
+        .with_body("<html>Bad Gateway</html>")
+        .expect(1)
+        .create();
+    let client = Client::from_async(
+        AsyncClient::new("-token")
+            .unwrap()
+            .with_host(server.url())
+            .with_retry_policy(
+                ExponentialBackoff::default()
+                    .with_initial_interval(Duration::from_millis(10))
+                    .with_randomization_factor(0.0)
+                    .with_retry_non_idempotent(true),
+            ),
+    )
+    .unwrap();
+    let ok_mock = server
+        .mock("GET", "/bot-token/close")
+        .with_body(r#"{"ok": true, "result": true}"#)
+        .create();
+    assert!(client.execute(Close).unwrap());
+    error_mock.assert();
+    ok_mock.assert();
+
+    server
+        .mock("GET", "/bot-token/close")
+        .with_body(r#"{"ok": false, "description": "test-error"}"#)
+        .create();
+    let err = client.execute(Close).unwrap_err();
+    assert!(matches!(err, ExecuteError::Response(ref err) if err.description() == "test-error"));
+}
+
+#[test]
+fn execute_with() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/bot-token/close")
+        .with_body(r#"{"ok": false, "description": "test", "parameters": {"retry_after": 0}}"#)
+        .expect(1)
+        .create();
+    let client = Client::from_async(AsyncClient::new("-token").unwrap().with_host(server.url())).unwrap();
+    let err = client
+        .execute_with(Close, ExecuteOptions::default().with_max_retries(0))
+        .unwrap_err();
+    assert!(matches!(err, ExecuteError::Response(ref err) if err.retry_after() == Some(0)));
+    mock.assert();
+}
+
+#[tokio::test]
+async fn async_runtime() {
+    let client = Client::new("-token").unwrap();
+    let err = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| client.execute(Close))).unwrap_err();
+    assert!(
+        err.downcast_ref::<&str>()
+            .unwrap()
+            .starts_with("The blocking client can not be used within an async runtime")
+    );
+    drop(client);
+}
+
+#[test]
+fn download_file() {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/file/bot-token/file-ok")
+        .with_body(b"file-data")
+        .create();
+    server
+        .mock("GET", "/file/bot-token/file-err")
+        .with_body("test-error")
+        .with_status(400)
+        .create();
+    let client = Client::from_async(AsyncClient::new("-token").unwrap().with_host(server.url())).unwrap();
+    let mut buf = Vec::new();
+    for chunk in client.download_file("file-ok").unwrap() {
+        buf.extend(chunk.unwrap());
+    }
+    assert_eq!(&buf[..], b"file-data");
+
+    let err = client.download_file("file-err").unwrap_err();
+    assert_eq!(err.to_string(), "failed to download file: status=400 text=test-error");
+}
+
+#[test]
+fn download_to_path() {
+    let mut server = Server::new();
+    let dir = tempfile::tempdir().unwrap();
+    server
+        .mock("POST", "/bot-token/getFile")
+        .with_body(r#"{"ok": true, "result": {"file_id": "file-id", "file_unique_id": "file-unique-id", "file_size": 9, "file_path": "file-path"}}"#)
+        .create();
+    server
+        .mock("GET", "/file/bot-token/file-path")
+        .with_body(b"file-data")
+        .create();
+    let client = Client::from_async(AsyncClient::new("-token").unwrap().with_host(server.url())).unwrap();
+    let path = dir.path().join("file.txt");
+    let file = client
+        .download_to_path("file-id", &path, DownloadOptions::default())
+        .unwrap();
+    assert_eq!(file.file_path.as_deref(), Some("file-path"));
+    assert_eq!(std::fs::read(&path).unwrap(), b"file-data");
+}
+
+#[test]
+fn download_to_writer() {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/file/bot-token/file-path")
+        .with_body(b"file-data")
+        .create();
+    let client = Client::from_async(AsyncClient::new("-token").unwrap().with_host(server.url())).unwrap();
+    let mut buf = Vec::new();
+    let file = tgbot::types::File::new("file-id", "file-unique-id").with_file_path("file-path");
+    client
+        .download_to_writer(file, &mut buf, DownloadOptions::default())
+        .unwrap();
+    assert_eq!(buf, b"file-data");
+}
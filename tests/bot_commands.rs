@@ -0,0 +1,100 @@
+#![allow(missing_docs)]
+use serde_json::json;
+use tgbot::types::{BotCommands, Message, ParseCommandError};
+
+#[derive(BotCommands, Debug, PartialEq)]
+enum Cmd {
+    /// Start the bot
+    Start { payload: Option<String> },
+    /// Ban a user
+    /// for a number of minutes
+    Ban { user: i64, minutes: u32 },
+    /// Echo a text
+    Echo(String),
+    /// Show help
+    #[command(rename = "help")]
+    ShowHelp,
+}
+
+fn create_message(text: &str) -> Message {
+    let length = text.find(' ').unwrap_or(text.len());
+    serde_json::from_value(json!({
+        "message_id": 1,
+        "date": 0,
+        "from": {"id": 1, "is_bot": false, "first_name": "test"},
+        "chat": {"id": 1, "type": "private", "first_name": "test"},
+        "text": text,
+        "entities": [{"type": "bot_command", "offset": 0, "length": length}]
+    }))
+    .unwrap()
+}
+
+fn parse(text: &str) -> Result<Cmd, ParseCommandError> {
+    Cmd::from_message(create_message(text))
+}
+
+#[test]
+fn parse_commands() {
+    assert_eq!(parse("/start").unwrap(), Cmd::Start { payload: None });
+    assert_eq!(
+        parse("/start ref").unwrap(),
+        Cmd::Start {
+            payload: Some(String::from("ref"))
+        }
+    );
+    assert_eq!(parse("/ban 1 30").unwrap(), Cmd::Ban { user: 1, minutes: 30 });
+    assert_eq!(
+        parse("/echo 'hello world'").unwrap(),
+        Cmd::Echo(String::from("hello world"))
+    );
+    assert_eq!(parse("/help").unwrap(), Cmd::ShowHelp);
+}
+
+#[test]
+fn parse_errors() {
+    assert!(matches!(
+        parse("/ban 1"),
+        Err(ParseCommandError::MissingArgument { name: "minutes" })
+    ));
+    let err = parse("/ban 1 -30").unwrap_err();
+    assert!(matches!(
+        err,
+        ParseCommandError::InvalidArgument { name: "minutes", ref value, .. } if value == "-30"
+    ));
+    assert_eq!(
+        err.to_string(),
+        "invalid argument minutes=-30: invalid digit found in string"
+    );
+    assert!(matches!(
+        parse("/help me"),
+        Err(ParseCommandError::TooManyArguments { expected: 0, actual: 1 })
+    ));
+    assert!(matches!(parse("/show_help"), Err(ParseCommandError::UnknownCommand(ref name)) if name == "/show_help"));
+    let message: Message = serde_json::from_value(json!({
+        "message_id": 1,
+        "date": 0,
+        "from": {"id": 1, "is_bot": false, "first_name": "test"},
+        "chat": {"id": 1, "type": "private", "first_name": "test"},
+        "text": "text"
+    }))
+    .unwrap();
+    assert!(matches!(Cmd::from_message(message), Err(ParseCommandError::Command(_))));
+}
+
+#[test]
+fn bot_commands() {
+    let commands: Vec<_> = Cmd::bot_commands()
+        .iter()
+        .map(|x| (x.name().to_string(), x.description().to_string()))
+        .collect();
+    assert_eq!(
+        commands,
+        [
+            ("start", "Start the bot"),
+            ("ban", "Ban a user for a number of minutes"),
+            ("echo", "Echo a text"),
+            ("help", "Show help"),
+        ]
+        .map(|(name, description)| (name.to_string(), description.to_string()))
+    );
+}
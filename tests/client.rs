@@ -1,12 +1,55 @@
 #![allow(missing_docs)]
-use std::{sync::Mutex, time::Instant};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
-use futures_util::stream::StreamExt;
-use mockito::{Server, ServerGuard};
+use futures_util::{future::BoxFuture, stream::StreamExt};
+use mockito::{Matcher, Request, Server, ServerGuard};
+use serde_json::Value as JsonValue;
 use tgbot::{
-    api::{Client, ExecuteError},
-    types::Close,
+    api::{
+        CancellationToken,
+        CassettePlayer,
+        CassetteRecorder,
+        Client,
+        ClientBuilder,
+        ClientObserver,
+        ClientPool,
+        DownloadFileError,
+        DownloadOptions,
+        ExecuteError,
+        ExecuteOptions,
+        ExponentialBackoff,
+        FileIdCache,
+        Interceptor,
+        Next,
+        Payload,
+        RawMethod,
+    },
+    types::{
+        ChatId,
+        ChatPeerId,
+        Close,
+        File,
+        InputFile,
+        InputFilePath,
+        InputFileReader,
+        InputMediaDocument,
+        InputMediaPhoto,
+        LogOut,
+        MediaGroup,
+        MediaGroupItem,
+        ReplaceManagedBotToken,
+        Response,
+        ResponseErrorKind,
+        SendDocument,
+        SendMediaGroup,
+        SendMessage,
+        UploadProgress,
+    },
 };
+use tokio::sync::watch;
 
 struct Cx {
     server: ServerGuard,
@@ -122,3 +165,964 @@ async fn download_file() {
         Err(err) => assert_eq!(err.to_string(), "failed to download file: status=400 text=test-error"),
     };
 }
+
+#[tokio::test]
+async fn download_to_path() {
+    let mut server = Server::new_async().await;
+    let dir = tempfile::tempdir().unwrap();
+    let client = Client::new("-token").unwrap().with_host(server.url());
+
+    let get_file_mock = server
+        .mock("POST", "/bot-token/getFile")
+        .match_body(r#"{"file_id":"file-id"}"#)
+        .with_body(r#"{"ok": true, "result": {"file_id": "file-id", "file_unique_id": "file-unique-id", "file_size": 9, "file_path": "file-path"}}"#)
+        .create();
+    let download_mock = server
+        .mock("GET", "/file/bot-token/file-path")
+        .match_header("range", Matcher::Missing)
+        .with_body(b"file-data")
+        .create();
+    let path = dir.path().join("file.txt");
+    let file = client
+        .download_to_path("file-id", &path, DownloadOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(file.file_path.unwrap(), "file-path");
+    assert_eq!(std::fs::read(&path).unwrap(), b"file-data");
+    assert!(!dir.path().join("file.txt.part").exists());
+    get_file_mock.assert();
+    download_mock.assert();
+
+    let resume_mock = server
+        .mock("GET", "/file/bot-token/file-path")
+        .match_header("range", "bytes=5-")
+        .with_status(206)
+        .with_body(b"data")
+        .create();
+    let path = dir.path().join("resumed.txt");
+    std::fs::write(dir.path().join("resumed.txt.part"), b"file-").unwrap();
+    let file = File::new("file-id", "file-unique-id")
+        .with_file_size(9)
+        .with_file_path("file-path");
+    client
+        .download_to_path(file.clone(), &path, DownloadOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), b"file-data");
+    resume_mock.assert();
+
+    let err = client
+        .download_to_path(file.clone(), &path, DownloadOptions::default().with_max_size(4))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DownloadFileError::FileTooLarge { max_size: 4 }));
+
+    let path = dir.path().join("mismatch.txt");
+    let err = client
+        .download_to_path(file.with_file_size(10), &path, DownloadOptions::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        DownloadFileError::FileSizeMismatch {
+            expected: 10,
+            actual: 9
+        }
+    ));
+    assert!(!path.exists());
+    assert!(!dir.path().join("mismatch.txt.part").exists());
+
+    let path = dir.path().join("large.txt");
+    let file = File::new("file-id", "file-unique-id").with_file_path("file-path");
+    let err = client
+        .download_to_path(file, &path, DownloadOptions::default().with_max_size(4))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DownloadFileError::FileTooLarge { max_size: 4 }));
+    assert!(!path.exists());
+    assert!(!dir.path().join("large.txt.part").exists());
+}
+
+#[tokio::test]
+async fn download_to_writer() {
+    let mut server = Server::new_async().await;
+    let client = Client::new("-token").unwrap().with_host(server.url());
+    server
+        .mock("GET", "/file/bot-token/file-path")
+        .with_body(b"file-data")
+        .create();
+
+    let file = File::new("file-id", "file-unique-id").with_file_path("file-path");
+    let mut buf = Vec::new();
+    client
+        .download_to_writer(file.clone(), &mut buf, DownloadOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(buf, b"file-data");
+
+    let mut buf = Vec::new();
+    let err = client
+        .download_to_writer(file, &mut buf, DownloadOptions::default().with_max_size(4))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DownloadFileError::FileTooLarge { max_size: 4 }));
+}
+
+struct BusinessConnectionInterceptor {
+    methods: Arc<Mutex<Vec<String>>>,
+}
+
+impl Interceptor for BusinessConnectionInterceptor {
+    fn intercept<'a>(
+        &'a self,
+        mut payload: Payload,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response<JsonValue>, ExecuteError>> {
+        Box::pin(async move {
+            self.methods.lock().unwrap().push(payload.method_name().to_string());
+            payload.set_field("business_connection_id", "business-id")?;
+            next.run(payload).await
+        })
+    }
+}
+
+struct CloseInterceptor;
+
+impl Interceptor for CloseInterceptor {
+    fn intercept<'a>(
+        &'a self,
+        payload: Payload,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response<JsonValue>, ExecuteError>> {
+        Box::pin(async move {
+            if payload.method_name() == "close" {
+                Ok(Response::Success(JsonValue::Bool(true)))
+            } else {
+                next.run(payload).await
+            }
+        })
+    }
+}
+
+#[tokio::test]
+async fn interceptor() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/bot-token/sendMessage")
+        .match_body(Matcher::PartialJson(serde_json::json!({
+            "chat_id": 1,
+            "text": "text",
+            "business_connection_id": "business-id"
+        })))
+        .with_body(
+            serde_json::to_vec(&serde_json::json!({
+                "ok": true,
+                "result": {
+                    "message_id": 1,
+                    "date": 0,
+                    "chat": {"id": 1, "type": "private", "first_name": "test"},
+                    "text": "text"
+                }
+            }))
+            .unwrap(),
+        )
+        .create();
+    let methods = Arc::new(Mutex::new(Vec::new()));
+    let client = Client::new("-token")
+        .unwrap()
+        .with_host(server.url())
+        .with_interceptor(BusinessConnectionInterceptor {
+            methods: methods.clone(),
+        })
+        .with_interceptor(CloseInterceptor);
+
+    let message = client.execute(SendMessage::new(1, "text")).await.unwrap();
+    assert_eq!(message.id, 1);
+    mock.assert();
+
+    assert!(client.execute(Close).await.unwrap());
+    assert_eq!(*methods.lock().unwrap(), vec!["sendMessage", "close"]);
+}
+
+#[tokio::test]
+async fn retry_policy() {
+    let mut server = Server::new_async().await;
+    let error_mock = server
+        .mock("GET", "/bot-token/close")
+        .with_status(502)
+        .with_body("<html>Bad Gateway</html>")
+        .expect(2)
+        .create();
+    let client = Client::new("-token").unwrap().with_host(server.url());
+    let err = client.execute(Close).await.unwrap_err();
+    assert!(matches!(err, ExecuteError::Http(ref err) if err.status().is_some_and(|x| x.as_u16() == 502)));
+
+    let client = client.with_retry_policy(
+        ExponentialBackoff::default()
+            .with_initial_interval(Duration::from_millis(10))
+            .with_randomization_factor(0.0)
+            .with_retry_non_idempotent(true),
+    );
+    let ok_mock = server
+        .mock("GET", "/bot-token/close")
+        .with_body(r#"{"ok": true, "result": true}"#)
+        .create();
+    assert!(client.execute(Close).await.unwrap());
+    error_mock.assert();
+    ok_mock.assert();
+}
+
+#[tokio::test]
+async fn chat_migration() {
+    let mut server = Server::new_async().await;
+    let group_mock = server
+        .mock("POST", "/bot-token/sendMessage")
+        .match_body(Matcher::PartialJson(serde_json::json!({"chat_id": -1})))
+        .with_body(
+            serde_json::to_vec(&serde_json::json!({
+                "ok": false,
+                "error_code": 400,
+                "description": "Bad Request: group chat was upgraded to a supergroup chat",
+                "parameters": {"migrate_to_chat_id": -1001}
+            }))
+            .unwrap(),
+        )
+        .expect(2)
+        .create();
+    let supergroup_mock = server
+        .mock("POST", "/bot-token/sendMessage")
+        .match_body(Matcher::PartialJson(serde_json::json!({"chat_id": -1001})))
+        .with_body(
+            serde_json::to_vec(&serde_json::json!({
+                "ok": true,
+                "result": {
+                    "message_id": 1,
+                    "date": 0,
+                    "chat": {"id": -1001, "type": "supergroup", "title": "test"},
+                    "text": "text"
+                }
+            }))
+            .unwrap(),
+        )
+        .create();
+
+    let client = Client::new("-token").unwrap().with_host(server.url());
+    let err = client.execute(SendMessage::new(-1, "text")).await.unwrap_err();
+    assert_eq!(err.response_error_kind(), Some(ResponseErrorKind::ChatMigrated));
+    match err {
+        ExecuteError::Response(err) => assert_eq!(err.migrate_to_chat_id(), Some(-1001)),
+        err => panic!("Unexpected error: {err:?}"),
+    }
+
+    let migrations = Arc::new(Mutex::new(Vec::new()));
+    let handler_migrations = migrations.clone();
+    let client = client.with_chat_migration_handler(move |from_chat_id, to_chat_id| {
+        let migrations = handler_migrations.clone();
+        async move {
+            migrations.lock().unwrap().push((from_chat_id, to_chat_id));
+        }
+    });
+    let message = client.execute(SendMessage::new(-1, "text")).await.unwrap();
+    assert_eq!(message.chat.get_id(), ChatPeerId::from(-1001));
+    assert_eq!(
+        *migrations.lock().unwrap(),
+        vec![(ChatId::from(-1), ChatPeerId::from(-1001))]
+    );
+    group_mock.assert();
+    supergroup_mock.assert();
+}
+
+#[tokio::test]
+async fn raw_method() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/bot-token/sendBrandNewThing")
+        .match_body(Matcher::Json(serde_json::json!({"chat_id": 1, "value": "test"})))
+        .with_body(r#"{"ok": true, "result": {"id": 1}}"#)
+        .create();
+    let client = Client::new("-token").unwrap().with_host(server.url());
+    let result = client
+        .execute(
+            RawMethod::new("sendBrandNewThing")
+                .with_field("chat_id", 1)
+                .with_field("value", "test"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result, serde_json::json!({"id": 1}));
+
+    #[derive(serde::Deserialize)]
+    struct Thing {
+        id: i64,
+    }
+    let thing = client
+        .execute(
+            RawMethod::json("sendBrandNewThing", serde_json::json!({"chat_id": 1, "value": "test"}))
+                .with_response_type::<Thing>(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(thing.id, 1);
+    mock.expect(2).assert();
+}
+
+fn mock_send_message_echo(server: &mut ServerGuard) -> mockito::Mock {
+    server
+        .mock("POST", "/bot-token/sendMessage")
+        .with_body_from_request(|request: &Request| {
+            let body: JsonValue = serde_json::from_slice(request.body().unwrap()).unwrap();
+            let chat_id = body["chat_id"].as_i64().unwrap();
+            serde_json::to_vec(&serde_json::json!({
+                "ok": true,
+                "result": {
+                    "message_id": chat_id,
+                    "date": 0,
+                    "chat": {"id": chat_id, "type": "private", "first_name": "test"},
+                    "text": body["text"]
+                }
+            }))
+            .unwrap()
+        })
+        .expect(10)
+        .create()
+}
+
+#[tokio::test]
+async fn execute_all() {
+    let mut server = Server::new_async().await;
+    let mock = mock_send_message_echo(&mut server);
+    let client = Client::new("-token").unwrap().with_host(server.url());
+    let results = client
+        .execute_all((1..=10).map(|chat_id| SendMessage::new(chat_id, "text")), 3)
+        .await;
+    let ids: Vec<i64> = results.into_iter().map(|result| result.unwrap().id).collect();
+    assert_eq!(ids, (1..=10).collect::<Vec<i64>>());
+    mock.assert();
+}
+
+#[tokio::test]
+async fn execute_stream() {
+    let mut server = Server::new_async().await;
+    let mock = mock_send_message_echo(&mut server);
+    let client = Client::new("-token").unwrap().with_host(server.url());
+    let mut results: Vec<(usize, i64)> = client
+        .execute_stream((1..=10).map(|chat_id| SendMessage::new(chat_id, "text")), 3)
+        .map(|(idx, result)| (idx, result.unwrap().id))
+        .collect()
+        .await;
+    results.sort();
+    assert_eq!(results, (0..10).map(|idx| (idx, idx as i64 + 1)).collect::<Vec<_>>());
+    mock.assert();
+}
+
+#[tokio::test]
+async fn local_mode() {
+    let mut server = Server::new_async().await;
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("document.txt");
+    std::fs::write(&file_path, b"file-data").unwrap();
+    let file_path = file_path.to_str().unwrap();
+
+    let client = Client::new("-token")
+        .unwrap()
+        .with_host(server.url())
+        .with_local_mode(true);
+    let mut stream = client.download_file(file_path).await.unwrap();
+    let mut buf = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        buf.extend(chunk.unwrap());
+    }
+    assert_eq!(&buf[..], b"file-data");
+
+    let err = match client.download_file("/not-found").await {
+        Ok(_) => panic!("Got an unexpected stream"),
+        Err(err) => err,
+    };
+    assert!(err.to_string().starts_with("failed to read or write file:"));
+
+    let mock = server
+        .mock("POST", "/bot-token/sendDocument")
+        .match_body(Matcher::Regex(format!("file://{}", regex::escape(file_path))))
+        .with_body(
+            serde_json::to_vec(&serde_json::json!({
+                "ok": true,
+                "result": {
+                    "message_id": 1,
+                    "date": 0,
+                    "chat": {"id": 1, "type": "private", "first_name": "test"},
+                    "document": {"file_id": "file-id", "file_unique_id": "file-unique-id"}
+                }
+            }))
+            .unwrap(),
+        )
+        .create();
+    let document = InputFile::path(file_path).await.unwrap();
+    client.execute(SendDocument::new(1, document)).await.unwrap();
+    mock.assert();
+
+    let photo_path = dir.path().join("my photo.jpg");
+    std::fs::write(&photo_path, b"photo-data").unwrap();
+    let photo_uri = format!("file://{}", dir.path().join("my%20photo.jpg").display());
+    let mock = server
+        .mock("POST", "/bot-token/sendMediaGroup")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(regex::escape(&format!(r#""media":"{photo_uri}""#))),
+            Matcher::Regex(regex::escape(&format!(r#""media":"file://{file_path}""#))),
+        ]))
+        .match_request(|request| {
+            let body = request.utf8_lossy_body().unwrap();
+            !body.contains("attach://") && !body.contains("file-data") && !body.contains("photo-data")
+        })
+        .with_body(serde_json::to_vec(&serde_json::json!({"ok": true, "result": []})).unwrap())
+        .create();
+    let media_group = MediaGroup::new([
+        MediaGroupItem::for_photo(InputFile::path(&photo_path).await.unwrap(), InputMediaPhoto::default()),
+        MediaGroupItem::for_document(InputFile::path(file_path).await.unwrap(), InputMediaDocument::default()),
+    ])
+    .unwrap();
+    client.execute(SendMediaGroup::new(1, media_group)).await.unwrap();
+    mock.assert();
+}
+
+#[tokio::test]
+async fn retry_file_upload() {
+    let mut server = Server::new_async().await;
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("document.txt");
+    std::fs::write(&file_path, b"file-data").unwrap();
+
+    let error_mock = server
+        .mock("POST", "/bot-token/sendDocument")
+        .match_body(Matcher::Regex(String::from("file-data")))
+        .with_status(502)
+        .with_body("<html>Bad Gateway</html>")
+        .expect(1)
+        .create();
+    let ok_mock = server
+        .mock("POST", "/bot-token/sendDocument")
+        .match_body(Matcher::Regex(String::from("file-data")))
+        .with_body(
+            serde_json::to_vec(&serde_json::json!({
+                "ok": true,
+                "result": {
+                    "message_id": 1,
+                    "date": 0,
+                    "chat": {"id": 1, "type": "private", "first_name": "test"},
+                    "document": {"file_id": "file-id", "file_unique_id": "file-unique-id"}
+                }
+            }))
+            .unwrap(),
+        )
+        .create();
+    let client = Client::new("-token")
+        .unwrap()
+        .with_host(server.url())
+        .with_retry_policy(
+            ExponentialBackoff::default()
+                .with_initial_interval(Duration::from_millis(10))
+                .with_randomization_factor(0.0)
+                .with_retry_non_idempotent(true),
+        );
+    let document = InputFile::path(&file_path).await.unwrap();
+    client.execute(SendDocument::new(1, document)).await.unwrap();
+    error_mock.assert();
+    ok_mock.assert();
+}
+
+#[tokio::test]
+async fn upload_progress() {
+    let mut server = Server::new_async().await;
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("document.txt");
+    std::fs::write(&file_path, b"file-data").unwrap();
+
+    let mock = server
+        .mock("POST", "/bot-token/sendDocument")
+        .match_body(Matcher::Regex(String::from("file-data")))
+        .with_body(
+            serde_json::to_vec(&serde_json::json!({
+                "ok": true,
+                "result": {
+                    "message_id": 1,
+                    "date": 0,
+                    "chat": {"id": 1, "type": "private", "first_name": "test"},
+                    "document": {"file_id": "file-id", "file_unique_id": "file-unique-id"}
+                }
+            }))
+            .unwrap(),
+        )
+        .expect(2)
+        .create();
+    let client = Client::new("-token").unwrap().with_host(server.url());
+
+    let (tx, rx) = watch::channel(UploadProgress::default());
+    let document = InputFilePath::new(&file_path).with_progress_handler(move |progress| {
+        tx.send_replace(progress);
+    });
+    client.execute(SendDocument::new(1, document)).await.unwrap();
+    let progress = *rx.borrow();
+    assert_eq!(progress.sent(), 9);
+    assert_eq!(progress.total(), Some(9));
+
+    let (tx, rx) = watch::channel(UploadProgress::default());
+    let document = InputFileReader::from(std::io::Cursor::new(b"file-data"))
+        .with_file_name("document.txt")
+        .with_progress_handler(move |progress| {
+            tx.send_replace(progress);
+        });
+    client.execute(SendDocument::new(1, document)).await.unwrap();
+    let progress = *rx.borrow();
+    assert_eq!(progress.sent(), 9);
+    assert_eq!(progress.total(), None);
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn file_id_cache() {
+    let mut server = Server::new_async().await;
+    let upload_mock = server
+        .mock("POST", "/bot-token/sendDocument")
+        .match_body(Matcher::Regex(String::from("file-data")))
+        .with_body(
+            serde_json::to_vec(&serde_json::json!({
+                "ok": true,
+                "result": {
+                    "message_id": 1,
+                    "date": 0,
+                    "chat": {"id": 1, "type": "private", "first_name": "test"},
+                    "document": {"file_id": "cached-file-id", "file_unique_id": "file-unique-id"}
+                }
+            }))
+            .unwrap(),
+        )
+        .expect(1)
+        .create();
+    let cached_mock = server
+        .mock("POST", "/bot-token/sendDocument")
+        .match_body(Matcher::Regex(String::from(
+            "name=\"document\"\r\n\r\ncached-file-id\r\n",
+        )))
+        .with_body(
+            serde_json::to_vec(&serde_json::json!({
+                "ok": true,
+                "result": {
+                    "message_id": 2,
+                    "date": 0,
+                    "chat": {"id": 1, "type": "private", "first_name": "test"},
+                    "document": {"file_id": "cached-file-id", "file_unique_id": "file-unique-id"}
+                }
+            }))
+            .unwrap(),
+        )
+        .expect(2)
+        .create();
+    let client = Client::new("-token")
+        .unwrap()
+        .with_host(server.url())
+        .with_interceptor(FileIdCache::default());
+    for _ in 0..3 {
+        let document = InputFileReader::from(std::io::Cursor::new(b"file-data")).with_file_name("document.txt");
+        client.execute(SendDocument::new(1, document)).await.unwrap();
+    }
+    upload_mock.assert();
+    cached_mock.assert();
+}
+
+fn mock_send_document(server: &mut ServerGuard, token: &str, body: &str, file_id: &str) -> mockito::Mock {
+    server
+        .mock("POST", format!("/bot{token}/sendDocument").as_str())
+        .match_body(Matcher::Regex(String::from(body)))
+        .with_body(
+            serde_json::to_vec(&serde_json::json!({
+                "ok": true,
+                "result": {
+                    "message_id": 1,
+                    "date": 0,
+                    "chat": {"id": 1, "type": "private", "first_name": "test"},
+                    "document": {"file_id": file_id, "file_unique_id": "file-unique-id"}
+                }
+            }))
+            .unwrap(),
+        )
+}
+
+#[tokio::test]
+async fn file_id_cache_per_bot() {
+    let mut server = Server::new_async().await;
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("document.txt");
+    std::fs::write(&file_path, b"file-data").unwrap();
+
+    let upload_mock_1 = mock_send_document(&mut server, "1:token", "file-data", "file-id-1")
+        .expect(1)
+        .create();
+    let cached_mock_1 = mock_send_document(&mut server, "1:token", "\r\n\r\nfile-id-1\r\n", "file-id-1")
+        .expect(1)
+        .create();
+    let upload_mock_2 = mock_send_document(&mut server, "2:token", "file-data", "file-id-2")
+        .expect(2)
+        .create();
+    let rejected_mock_2 = server
+        .mock("POST", "/bot2:token/sendDocument")
+        .match_body(Matcher::Regex(String::from("\r\n\r\nfile-id-2\r\n")))
+        .with_body(
+            serde_json::to_vec(&serde_json::json!({
+                "ok": false,
+                "error_code": 400,
+                "description": "Bad Request: wrong file identifier/HTTP URL specified"
+            }))
+            .unwrap(),
+        )
+        .expect(1)
+        .create();
+
+    let client_1 = Client::new("1:token")
+        .unwrap()
+        .with_host(server.url())
+        .with_interceptor(FileIdCache::default());
+    let client_2 = client_1.clone().with_token("2:token");
+    for client in [&client_1, &client_2, &client_1, &client_2] {
+        let document = InputFile::path(&file_path).await.unwrap();
+        client.execute(SendDocument::new(1, document)).await.unwrap();
+    }
+    upload_mock_1.assert();
+    cached_mock_1.assert();
+    upload_mock_2.assert();
+    rejected_mock_2.assert();
+}
+
+#[tokio::test]
+async fn cassette() {
+    let mut server = Server::new_async().await;
+    let dir = tempfile::tempdir().unwrap();
+    let cassette_path = dir.path().join("cassette.json");
+    let file_path = dir.path().join("document.txt");
+    std::fs::write(&file_path, b"file-data").unwrap();
+
+    let send_message_mock = server
+        .mock("POST", "/bot-token/sendMessage")
+        .with_body(
+            serde_json::to_vec(&serde_json::json!({
+                "ok": true,
+                "result": {
+                    "message_id": 1,
+                    "date": 0,
+                    "chat": {"id": 1, "type": "private", "first_name": "test"},
+                    "text": "text"
+                }
+            }))
+            .unwrap(),
+        )
+        .create();
+    let send_document_mock = server
+        .mock("POST", "/bot-token/sendDocument")
+        .with_body(r#"{"ok": false, "description": "Bad Request: chat not found", "error_code": 400, "extra": 1}"#)
+        .create();
+    let replace_token_mock = server
+        .mock("POST", "/bot-token/replaceManagedBotToken")
+        .with_body(r#"{"ok": true, "result": "123:secret"}"#)
+        .create();
+    let client = Client::new("-token")
+        .unwrap()
+        .with_host(server.url())
+        .with_interceptor(CassetteRecorder::new(&cassette_path));
+    let message = client.execute(SendMessage::new(1, "text")).await.unwrap();
+    let document = InputFile::path(&file_path).await.unwrap();
+    let err = client.execute(SendDocument::new(1, document)).await.unwrap_err();
+    assert_eq!(err.response_error_kind(), Some(ResponseErrorKind::ChatNotFound));
+    let token = client.execute(ReplaceManagedBotToken::from(123)).await.unwrap();
+    assert_eq!(token, "123:secret");
+    send_message_mock.assert();
+    send_document_mock.assert();
+    replace_token_mock.assert();
+    assert!(!cassette_path.exists());
+    drop(client);
+
+    let cassette: JsonValue = serde_json::from_slice(&std::fs::read(&cassette_path).unwrap()).unwrap();
+    let interactions = cassette["interactions"].as_array().unwrap();
+    assert_eq!(interactions.len(), 3);
+    assert_eq!(
+        interactions[0]["request"],
+        serde_json::json!({"method": "sendMessage", "fields": {"chat_id": 1, "text": "text"}})
+    );
+    assert_eq!(interactions[1]["request"]["fields"]["chat_id"], "1");
+    assert_eq!(
+        interactions[1]["request"]["files"]["document"]["file_name"],
+        "document.txt"
+    );
+    assert_eq!(interactions[1]["response"]["error_code"], 400);
+    assert_eq!(interactions[1]["response"]["extra"], 1);
+    assert_eq!(interactions[2]["response"]["result"], "123:***");
+    assert!(!std::fs::read_to_string(&cassette_path).unwrap().contains("secret"));
+
+    let player = CassettePlayer::open(&cassette_path).await.unwrap();
+    let client = Client::new("-token")
+        .unwrap()
+        .with_host("http://127.0.0.1:0")
+        .with_interceptor(player);
+    let replayed_message = client.execute(SendMessage::new(1, "text")).await.unwrap();
+    assert_eq!(replayed_message.id, message.id);
+    assert_eq!(replayed_message.get_text().unwrap().data, "text");
+    let document = InputFile::path(&file_path).await.unwrap();
+    let err = client.execute(SendDocument::new(1, document)).await.unwrap_err();
+    assert_eq!(err.response_error_kind(), Some(ResponseErrorKind::ChatNotFound));
+    let token = client.execute(ReplaceManagedBotToken::from(123)).await.unwrap();
+    assert_eq!(token, "123:***");
+}
+
+async fn create_cassette_client(dir: &tempfile::TempDir, interaction: JsonValue) -> Client {
+    let cassette_path = dir.path().join("cassette.json");
+    let cassette = serde_json::json!({"interactions": [interaction]});
+    std::fs::write(&cassette_path, serde_json::to_vec(&cassette).unwrap()).unwrap();
+    let player = CassettePlayer::open(&cassette_path).await.unwrap();
+    Client::new("-token")
+        .unwrap()
+        .with_host("http://127.0.0.1:0")
+        .with_interceptor(player)
+}
+
+#[tokio::test]
+#[should_panic(expected = "Unexpected method logOut, expected close")]
+async fn cassette_unexpected_method() {
+    let dir = tempfile::tempdir().unwrap();
+    let client = create_cassette_client(
+        &dir,
+        serde_json::json!({"request": {"method": "close"}, "response": {"ok": true, "result": true}}),
+    )
+    .await;
+    let _ = client.execute(LogOut).await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "Unexpected fields of sendMessage")]
+async fn cassette_unexpected_fields() {
+    let dir = tempfile::tempdir().unwrap();
+    let client = create_cassette_client(
+        &dir,
+        serde_json::json!({
+            "request": {"method": "sendMessage", "fields": {"chat_id": 1, "text": "text"}},
+            "response": {"ok": true, "result": true}
+        }),
+    )
+    .await;
+    let _ = client.execute(SendMessage::new(2, "text")).await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "No recorded response for close")]
+async fn cassette_no_response() {
+    let dir = tempfile::tempdir().unwrap();
+    let client = create_cassette_client(
+        &dir,
+        serde_json::json!({"request": {"method": "close"}, "response": {"ok": true, "result": true}}),
+    )
+    .await;
+    assert!(client.execute(Close).await.unwrap());
+    let _ = client.execute(Close).await;
+}
+
+#[derive(Clone, Default)]
+struct EventObserver {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl ClientObserver for EventObserver {
+    fn on_request_start(&self, method_name: &str) {
+        self.events.lock().unwrap().push(format!("start {method_name}"));
+    }
+
+    fn on_retry(&self, method_name: &str, attempt: u32, _delay: Duration, _error: &ExecuteError) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("retry {method_name} {attempt}"));
+    }
+
+    fn on_flood_wait(&self, method_name: &str, retry_after: Duration) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("flood wait {method_name} {}", retry_after.as_secs()));
+    }
+
+    fn on_request_complete(&self, method_name: &str, _duration: Duration, error: Option<&ExecuteError>) {
+        self.events.lock().unwrap().push(format!(
+            "complete {method_name} {:?}",
+            error.and_then(|x| x.response_error_kind())
+        ));
+    }
+}
+
+#[tokio::test]
+async fn observer() {
+    let mut server = Server::new_async().await;
+    let flood_mock = server
+        .mock("GET", "/bot-token/close")
+        .with_body(
+            r#"{"ok": false, "description": "Too Many Requests", "error_code": 429, "parameters": {"retry_after": 0}}"#,
+        )
+        .expect(1)
+        .create();
+    let error_mock = server
+        .mock("GET", "/bot-token/close")
+        .with_status(502)
+        .with_body("<html>Bad Gateway</html>")
+        .expect(1)
+        .create();
+    let ok_mock = server
+        .mock("GET", "/bot-token/close")
+        .with_body(r#"{"ok": true, "result": true}"#)
+        .expect(1)
+        .create();
+    let unauthorized_mock = server
+        .mock("GET", "/bot-token/logOut")
+        .with_body(r#"{"ok": false, "description": "Unauthorized", "error_code": 401}"#)
+        .create();
+    let observer = EventObserver::default();
+    let client = Client::new("-token")
+        .unwrap()
+        .with_host(server.url())
+        .with_retry_policy(
+            ExponentialBackoff::default()
+                .with_initial_interval(Duration::from_millis(10))
+                .with_randomization_factor(0.0)
+                .with_retry_non_idempotent(true),
+        )
+        .with_observer(observer.clone());
+    assert!(client.execute(Close).await.unwrap());
+    client.execute(LogOut).await.unwrap_err();
+    flood_mock.assert();
+    error_mock.assert();
+    ok_mock.assert();
+    unauthorized_mock.assert();
+    assert_eq!(
+        *observer.events.lock().unwrap(),
+        vec![
+            "start close",
+            "flood wait close 0",
+            "retry close 1",
+            "complete close None",
+            "start logOut",
+            "complete logOut Some(Unauthorized)",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn client_pool() {
+    let mut server = Server::new_async().await;
+    let get_token_mock = server
+        .mock("POST", "/botmanager-token/getManagedBotToken")
+        .match_body(r#"{"user_id":1}"#)
+        .with_body(r#"{"ok": true, "result": "1:token"}"#)
+        .expect(1)
+        .create();
+    let replace_token_mock = server
+        .mock("POST", "/botmanager-token/replaceManagedBotToken")
+        .match_body(r#"{"user_id":1}"#)
+        .with_body(r#"{"ok": true, "result": "1:new-token"}"#)
+        .expect(2)
+        .create();
+    let old_token_mock = server
+        .mock("GET", "/bot1:token/close")
+        .with_body(r#"{"ok": true, "result": true}"#)
+        .expect(1)
+        .create();
+    let new_token_mock = server
+        .mock("GET", "/bot1:new-token/close")
+        .with_body(r#"{"ok": true, "result": true}"#)
+        .expect(3)
+        .create();
+
+    let pool = ClientPool::new(Client::new("manager-token").unwrap().with_host(server.url()));
+    assert!(pool.get(1).is_none());
+    let client = pool.get_or_fetch(1).await.unwrap();
+    assert!(client.execute(Close).await.unwrap());
+    pool.get_or_fetch(1).await.unwrap();
+    get_token_mock.assert();
+    old_token_mock.assert();
+
+    let new_client = pool.replace_token(1).await.unwrap();
+    assert!(new_client.execute(Close).await.unwrap());
+    assert!(client.execute(Close).await.unwrap());
+
+    pool.insert(1, "1:token");
+    let token: String = pool.manager().execute(ReplaceManagedBotToken::from(1)).await.unwrap();
+    assert_eq!(token, "1:new-token");
+    assert!(pool.get(1).unwrap().execute(Close).await.unwrap());
+    replace_token_mock.assert();
+    new_token_mock.assert();
+
+    assert!(pool.remove(1).is_some());
+    assert!(pool.get(1).is_none());
+}
+
+#[tokio::test]
+async fn execute_with() {
+    let mut server = Server::new_async().await;
+    let flood_mock = server
+        .mock("GET", "/bot-token/close")
+        .with_body(r#"{"ok": false, "description": "Too Many Requests", "parameters": {"retry_after": 60}}"#)
+        .expect(1)
+        .create();
+    let client = Client::new("-token").unwrap().with_host(server.url());
+
+    let started_at = Instant::now();
+    let options = ExecuteOptions::default().with_timeout(Duration::from_millis(100));
+    let err = client.execute_with(Close, options).await.unwrap_err();
+    assert!(matches!(err, ExecuteError::Timeout));
+    assert!(started_at.elapsed() < Duration::from_secs(5));
+    flood_mock.assert();
+
+    let cancellation_token = CancellationToken::new();
+    let options = ExecuteOptions::default().with_cancellation_token(cancellation_token.clone());
+    let cancel = async {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        cancellation_token.cancel();
+    };
+    let (result, ()) = tokio::join!(client.execute_with(Close, options), cancel);
+    assert!(matches!(result.unwrap_err(), ExecuteError::Cancelled));
+
+    let options = ExecuteOptions::default().with_max_retries(0);
+    let err = client.execute_with(Close, options).await.unwrap_err();
+    assert_eq!(err.response_error_kind(), Some(ResponseErrorKind::TooManyRequests));
+}
+
+#[tokio::test]
+async fn client_builder() {
+    let mut server = Server::new_async().await;
+    let flood_mock = server
+        .mock("GET", "/bot-token/close")
+        .match_header("user-agent", "test-agent")
+        .with_body(r#"{"ok": false, "description": "Too Many Requests", "parameters": {"retry_after": 60}}"#)
+        .expect(2)
+        .create();
+    let client = ClientBuilder::new("-token")
+        .with_host(server.url())
+        .with_user_agent("test-agent")
+        .with_connect_timeout(Duration::from_secs(5))
+        .with_read_timeout(Duration::from_secs(5))
+        .with_max_retries(1)
+        .with_max_retry_after(0)
+        .build()
+        .unwrap();
+    let err = client.execute(Close).await.unwrap_err();
+    assert_eq!(err.response_error_kind(), Some(ResponseErrorKind::TooManyRequests));
+    flood_mock.assert();
+}
+
+#[tokio::test]
+async fn token_redaction() {
+    let client = Client::new("123:secret").unwrap().with_host("http://127.0.0.1:1");
+    let err = client.execute(Close).await.unwrap_err();
+    assert!(matches!(err, ExecuteError::Http(_)));
+    let err = format!("{err} {err:?}");
+    assert!(!err.contains("secret"), "{err}");
+    assert!(err.contains("/bot123:***/close"), "{err}");
+
+    let err = client.download_file("file-path").await.err().unwrap();
+    let err = format!("{err} {err:?}");
+    assert!(!err.contains("secret"), "{err}");
+    assert!(err.contains("/file/bot123:***/file-path"), "{err}");
+}
@@ -0,0 +1,183 @@
+#![allow(missing_docs)]
+use std::time::Duration;
+
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tgbot::{
+    handler::{
+        Dialogue,
+        DialogueScope,
+        DialogueStorage,
+        InMemoryDialogueStorage,
+        JsonFileDialogueStorage,
+        UpdateHandler,
+    },
+    types::Update,
+};
+
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+enum State {
+    #[default]
+    Start,
+    ReceiveName,
+    ReceiveAge {
+        name: String,
+    },
+}
+
+async fn handle(update: Update, state: State) -> Option<State> {
+    let text = update.get_message().and_then(|x| x.get_text()).map(|x| x.data.clone());
+    match (state, text) {
+        (State::Start, _) => Some(State::ReceiveName),
+        (State::ReceiveName, Some(name)) => Some(State::ReceiveAge { name }),
+        (State::ReceiveAge { .. }, Some(_)) => None,
+        (state, None) => Some(state),
+    }
+}
+
+fn create_message(chat_id: i64, user_id: i64, text: &str) -> Update {
+    serde_json::from_value(json!({
+        "update_id": 1,
+        "message": {
+            "message_id": 1,
+            "date": 0,
+            "from": {"id": user_id, "is_bot": false, "first_name": "John"},
+            "chat": {"id": chat_id, "type": "supergroup", "title": "Chat"},
+            "text": text
+        }
+    }))
+    .unwrap()
+}
+
+async fn get_state<T: DialogueStorage>(storage: &T, key: &str) -> Option<State> {
+    storage
+        .get(key)
+        .await
+        .unwrap()
+        .map(|state| serde_json::from_value(state).unwrap())
+}
+
+#[tokio::test]
+async fn dialogue_in_memory() {
+    let dialogue = Dialogue::new(InMemoryDialogueStorage::default(), handle);
+
+    dialogue.handle(create_message(-1, 1, "/start")).await;
+    assert_eq!(get_state(dialogue.storage(), "-1:1").await, Some(State::ReceiveName));
+    assert_eq!(get_state(dialogue.storage(), "-1:2").await, None);
+
+    dialogue.handle(create_message(-1, 2, "/start")).await;
+    dialogue.handle(create_message(-1, 1, "John")).await;
+    assert_eq!(
+        get_state(dialogue.storage(), "-1:1").await,
+        Some(State::ReceiveAge {
+            name: String::from("John")
+        })
+    );
+    assert_eq!(get_state(dialogue.storage(), "-1:2").await, Some(State::ReceiveName));
+
+    dialogue.handle(create_message(-1, 1, "42")).await;
+    assert_eq!(get_state(dialogue.storage(), "-1:1").await, None);
+
+    let dialogue = Dialogue::new(InMemoryDialogueStorage::default(), handle).with_scope(DialogueScope::Chat);
+    dialogue.handle(create_message(-1, 1, "/start")).await;
+    dialogue.handle(create_message(-1, 2, "Jane")).await;
+    assert_eq!(
+        get_state(dialogue.storage(), "chat:-1").await,
+        Some(State::ReceiveAge {
+            name: String::from("Jane")
+        })
+    );
+
+    let dialogue = Dialogue::new(InMemoryDialogueStorage::default(), handle).with_scope(DialogueScope::User);
+    dialogue.handle(create_message(-1, 1, "/start")).await;
+    dialogue.handle(create_message(-2, 1, "John")).await;
+    assert!(get_state(dialogue.storage(), "user:1").await.is_some());
+}
+
+#[tokio::test]
+async fn dialogue_concurrent_updates() {
+    async fn count(_update: Update, state: u64) -> Option<u64> {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        Some(state + 1)
+    }
+
+    let dialogue = Dialogue::new(InMemoryDialogueStorage::default(), count);
+    join_all((0..10).map(|_| dialogue.handle(create_message(-1, 1, "text")))).await;
+    assert_eq!(dialogue.storage().get("-1:1").await.unwrap(), Some(json!(10)));
+}
+
+#[tokio::test]
+async fn dialogue_invalid_state() {
+    let storage = InMemoryDialogueStorage::default();
+    storage.set(String::from("-1:1"), json!("Unknown"), None).await.unwrap();
+    let dialogue = Dialogue::new(storage, handle);
+    dialogue.handle(create_message(-1, 1, "/start")).await;
+    assert_eq!(dialogue.storage().get("-1:1").await.unwrap(), Some(json!("Unknown")));
+}
+
+#[tokio::test(start_paused = true)]
+async fn dialogue_ttl() {
+    let dialogue = Dialogue::new(InMemoryDialogueStorage::default(), handle).with_ttl(Some(Duration::from_secs(60)));
+    dialogue.handle(create_message(-1, 1, "/start")).await;
+    tokio::time::advance(Duration::from_secs(30)).await;
+    dialogue.handle(create_message(-1, 1, "John")).await;
+    tokio::time::advance(Duration::from_secs(30)).await;
+    assert!(get_state(dialogue.storage(), "-1:1").await.is_some());
+    tokio::time::advance(Duration::from_secs(31)).await;
+    assert_eq!(get_state(dialogue.storage(), "-1:1").await, None);
+
+    dialogue.handle(create_message(-1, 1, "John")).await;
+    assert_eq!(get_state(dialogue.storage(), "-1:1").await, Some(State::ReceiveName));
+
+    let dialogue = Dialogue::new(InMemoryDialogueStorage::default(), handle).with_ttl(None);
+    dialogue.handle(create_message(-1, 1, "/start")).await;
+    tokio::time::advance(Duration::from_secs(86400 * 365)).await;
+    assert!(get_state(dialogue.storage(), "-1:1").await.is_some());
+}
+
+#[tokio::test]
+async fn dialogue_json_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("dialogues").join("state.json");
+
+    let storage = JsonFileDialogueStorage::open(&path).await.unwrap();
+    assert!(!path.exists());
+    let dialogue = Dialogue::new(storage, handle);
+    dialogue.handle(create_message(-1, 1, "/start")).await;
+    dialogue.handle(create_message(-1, 1, "John")).await;
+    dialogue.handle(create_message(-1, 2, "/start")).await;
+    drop(dialogue);
+
+    assert!(!dir.path().join("dialogues").join("state.json.tmp").exists());
+    let storage = JsonFileDialogueStorage::open(&path).await.unwrap();
+    assert_eq!(storage.path(), path);
+    assert_eq!(
+        get_state(&storage, "-1:1").await,
+        Some(State::ReceiveAge {
+            name: String::from("John")
+        })
+    );
+    let dialogue = Dialogue::new(storage, handle);
+    dialogue.handle(create_message(-1, 2, "Jane")).await;
+    dialogue.handle(create_message(-1, 1, "42")).await;
+    drop(dialogue);
+
+    let storage = JsonFileDialogueStorage::open(&path).await.unwrap();
+    assert_eq!(get_state(&storage, "-1:1").await, None);
+    assert_eq!(
+        get_state(&storage, "-1:2").await,
+        Some(State::ReceiveAge {
+            name: String::from("Jane")
+        })
+    );
+
+    storage
+        .set(String::from("expired"), json!("Start"), Some(Duration::ZERO))
+        .await
+        .unwrap();
+    assert_eq!(get_state(&storage, "expired").await, None);
+
+    std::fs::write(&path, "not json").unwrap();
+    assert!(JsonFileDialogueStorage::open(&path).await.is_err());
+}
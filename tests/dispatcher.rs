@@ -0,0 +1,84 @@
+#![allow(missing_docs)]
+use std::sync::{Arc, Mutex};
+
+use serde_json::json;
+use tgbot::{
+    handler::{DispatchMode, Dispatcher, Filter, UpdateHandler},
+    types::{CallbackQuery, Update},
+};
+
+type Calls = Arc<Mutex<Vec<(&'static str, i64)>>>;
+
+fn create_dispatcher(calls: &Calls, mode: DispatchMode) -> Dispatcher {
+    let route = |name: &'static str| {
+        let calls = calls.clone();
+        move |update: Update| {
+            let calls = calls.clone();
+            async move {
+                calls.lock().unwrap().push((name, update.id));
+            }
+        }
+    };
+    Dispatcher::default()
+        .with_mode(mode)
+        .with_route(Filter::command("start"), route("start"))
+        .with_route(Filter::user_id(1), route("user"))
+        .with_route(Filter::kind::<CallbackQuery>(), route("callback_query"))
+        .with_default_handler(route("default"))
+}
+
+fn create_message(id: i64, text: &str) -> Update {
+    let entities = if text.starts_with('/') {
+        json!([{"type": "bot_command", "offset": 0, "length": text.len()}])
+    } else {
+        json!([])
+    };
+    serde_json::from_value(json!({
+        "update_id": id,
+        "message": {
+            "message_id": 1,
+            "date": 0,
+            "from": {"id": 1, "is_bot": false, "first_name": "test"},
+            "chat": {"id": 1, "type": "private", "first_name": "test"},
+            "text": text,
+            "entities": entities
+        }
+    }))
+    .unwrap()
+}
+
+fn create_poll(id: i64) -> Update {
+    serde_json::from_value(json!({
+        "update_id": id,
+        "poll": {
+            "id": "poll-id",
+            "question": "Question",
+            "options": [],
+            "total_voter_count": 0,
+            "is_closed": false,
+            "is_anonymous": true,
+            "type": "regular",
+            "allows_multiple_answers": false
+        }
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn dispatcher_first_match() {
+    let calls = Calls::default();
+    let dispatcher = create_dispatcher(&calls, DispatchMode::FirstMatch);
+    dispatcher.handle(create_message(1, "/start")).await;
+    dispatcher.handle(create_message(2, "text")).await;
+    dispatcher.handle(create_poll(3)).await;
+    assert_eq!(*calls.lock().unwrap(), [("start", 1), ("user", 2), ("default", 3)]);
+}
+
+#[tokio::test]
+async fn dispatcher_fan_out() {
+    let calls = Calls::default();
+    let dispatcher = create_dispatcher(&calls, DispatchMode::FanOut);
+    dispatcher.handle(create_message(1, "/start")).await;
+    dispatcher.handle(create_poll(2)).await;
+    assert_eq!(*calls.lock().unwrap(), [("start", 1), ("user", 1), ("default", 2)]);
+}
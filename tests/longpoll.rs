@@ -1,5 +1,6 @@
 #![allow(missing_docs)]
 use std::{
+    collections::HashMap,
     sync::{
         Arc,
         atomic::{AtomicUsize, Ordering},
@@ -12,8 +13,8 @@ use mockito::{Matcher, Server};
 use serde_json::json;
 use tgbot::{
     api::Client,
-    handler::{LongPoll, LongPollOptions, UpdateHandler},
-    types::Update,
+    handler::{LongPoll, LongPollOptions, LongPollSummary, UpdateHandler, UpdateOrdering},
+    types::{ChatPeerId, Update},
 };
 use tokio::{spawn, sync::Mutex, time::sleep};
 
@@ -73,6 +74,12 @@ async fn longpoll() {
             .unwrap(),
         )
         .create();
+    let confirm = server
+        .mock("POST", "/bot-token/getUpdates")
+        .match_body(Matcher::PartialJson(json!({"offset": 2, "timeout": 0, "limit": 1})))
+        .with_body(serde_json::to_vec(&json!({"ok": true, "result": []})).unwrap())
+        .expect(1)
+        .create();
     let client = Client::new("-token").unwrap().with_host(server.url());
     let updates = Arc::new(Mutex::new(Vec::new()));
     let handler = Handler {
@@ -91,8 +98,10 @@ async fn longpoll() {
         }
         handle.shutdown().await
     });
-    poll.run().await;
-    assert!(!updates.lock().await.is_empty())
+    let summary = poll.run().await;
+    assert!(!updates.lock().await.is_empty());
+    assert_eq!(summary.confirmed_offset, Some(2));
+    confirm.assert();
 }
 
 struct BoundedHandler {
@@ -163,3 +172,193 @@ async fn longpoll_concurrency_limit() {
     let max = max_active.load(Ordering::Relaxed);
     assert!(max <= limit, "Max concurrent was {}, but expected {}", max, limit);
 }
+
+#[derive(Default)]
+struct ChatHandler {
+    active: std::sync::Mutex<HashMap<ChatPeerId, usize>>,
+    updates: std::sync::Mutex<Vec<(ChatPeerId, i64)>>,
+    max_active: AtomicUsize,
+}
+
+impl UpdateHandler for ChatHandler {
+    async fn handle(&self, update: Update) {
+        let chat_id = update.get_chat_id().unwrap();
+        {
+            let mut active = self.active.lock().unwrap();
+            let count = active.entry(chat_id).or_default();
+            *count += 1;
+            self.max_active.fetch_max(*count, Ordering::AcqRel);
+        }
+        sleep(Duration::from_millis(5)).await;
+        self.updates.lock().unwrap().push((chat_id, update.id));
+        *self.active.lock().unwrap().get_mut(&chat_id).unwrap() -= 1;
+    }
+}
+
+#[tokio::test]
+async fn longpoll_ordering() {
+    let mut server = Server::new_async().await;
+
+    let updates: Vec<serde_json::Value> = (1..=10)
+        .map(|i| {
+            json!({
+                "update_id": i,
+                "message": {
+                    "message_id": i,
+                    "date": 0,
+                    "from": {"id": 1, "is_bot": false, "first_name": "test"},
+                    "chat": {"id": i % 2, "type": "private", "first_name": "test"},
+                    "text": "test"
+                }
+            })
+        })
+        .collect();
+    server
+        .mock("POST", "/bot-token/getUpdates")
+        .match_body(Matcher::PartialJson(json!({"offset": 1})))
+        .with_body(serde_json::to_vec(&json!({"ok": true, "result": updates})).unwrap())
+        .create();
+    server
+        .mock("POST", "/bot-token/getUpdates")
+        .match_body(Matcher::PartialJson(json!({"offset": 11})))
+        .with_body(serde_json::to_vec(&json!({"ok": true, "result": []})).unwrap())
+        .create();
+
+    let client = Client::new("-token").unwrap().with_host(server.url());
+    let handler = Arc::new(ChatHandler::default());
+    let options = LongPollOptions::default()
+        .with_poll_timeout(Duration::ZERO)
+        .with_ordering(UpdateOrdering::per_chat().with_queue_size(2));
+    let poll = LongPoll::new(client, handler.clone()).with_options(options);
+    let handle = poll.get_handle();
+    spawn(async move {
+        sleep(Duration::from_millis(300)).await;
+        handle.shutdown().await;
+    });
+    poll.run().await;
+    sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(handler.max_active.load(Ordering::Relaxed), 1);
+    let updates = handler.updates.lock().unwrap();
+    assert_eq!(updates.len(), 10);
+    for chat_id in [0, 1] {
+        let ids: Vec<_> = updates
+            .iter()
+            .filter(|(x, _)| *x == ChatPeerId::from(chat_id))
+            .map(|(_, id)| *id)
+            .collect();
+        assert!(ids.is_sorted(), "{ids:?}");
+        assert_eq!(ids.len(), 5);
+    }
+}
+
+struct SlowHandler {
+    delays: HashMap<i64, Duration>,
+}
+
+impl UpdateHandler for SlowHandler {
+    async fn handle(&self, update: Update) {
+        let delay = self.delays[&update.id];
+        // Zero delay stands for a failing handler
+        assert!(!delay.is_zero(), "Could not handle update {}", update.id);
+        sleep(delay).await;
+    }
+}
+
+async fn run_shutdown(delays: &[(i64, u64)], shutdown_timeout: Duration, confirmed_offset: i64) -> LongPollSummary {
+    let mut server = Server::new_async().await;
+    let updates: Vec<serde_json::Value> = delays
+        .iter()
+        .map(|(id, _)| {
+            json!({
+                "update_id": id,
+                "message": {
+                    "message_id": id,
+                    "date": 0,
+                    "from": {"id": 1, "is_bot": false, "first_name": "test"},
+                    "chat": {"id": 1, "type": "private", "first_name": "test"},
+                    "text": "test"
+                }
+            })
+        })
+        .collect();
+    let last_id = delays.last().unwrap().0;
+    server
+        .mock("POST", "/bot-token/getUpdates")
+        .match_body(Matcher::PartialJson(json!({"offset": 1, "timeout": 10})))
+        .with_body(serde_json::to_vec(&json!({"ok": true, "result": updates})).unwrap())
+        .create();
+    server
+        .mock("POST", "/bot-token/getUpdates")
+        .match_body(Matcher::PartialJson(json!({"offset": last_id + 1, "timeout": 10})))
+        .with_body(serde_json::to_vec(&json!({"ok": true, "result": []})).unwrap())
+        .create();
+    let confirm = server
+        .mock("POST", "/bot-token/getUpdates")
+        .match_body(Matcher::PartialJson(
+            json!({"offset": confirmed_offset, "timeout": 0, "limit": 1}),
+        ))
+        .with_body(serde_json::to_vec(&json!({"ok": true, "result": []})).unwrap())
+        .expect(1)
+        .create();
+
+    let client = Client::new("-token").unwrap().with_host(server.url());
+    let handler = SlowHandler {
+        delays: delays
+            .iter()
+            .map(|(id, delay)| (*id, Duration::from_millis(*delay)))
+            .collect(),
+    };
+    let options = LongPollOptions::default().with_shutdown_timeout(shutdown_timeout);
+    let poll = LongPoll::new(client, handler).with_options(options);
+    let handle = poll.get_handle();
+    let shutdown = spawn(async move {
+        sleep(Duration::from_millis(50)).await;
+        handle.shutdown().await
+    });
+    let summary = poll.run().await;
+    assert_eq!(shutdown.await.unwrap(), Some(summary));
+    confirm.assert();
+    summary
+}
+
+#[tokio::test]
+async fn longpoll_shutdown() {
+    let summary = run_shutdown(&[(1, 10), (2, 200), (3, 100)], Duration::from_secs(5), 4).await;
+    assert_eq!(
+        summary,
+        LongPollSummary {
+            received: 3,
+            completed: 3,
+            abandoned: 0,
+            confirmed_offset: Some(4),
+        }
+    );
+
+    let summary = run_shutdown(&[(1, 10), (2, 5000), (3, 10)], Duration::from_millis(100), 2).await;
+    assert_eq!(
+        summary,
+        LongPollSummary {
+            received: 3,
+            completed: 2,
+            abandoned: 1,
+            confirmed_offset: Some(2),
+        }
+    );
+}
+
+#[tokio::test]
+async fn longpoll_shutdown_panic() {
+    let started_at = Instant::now();
+    let summary = run_shutdown(&[(1, 10), (2, 0), (3, 10)], Duration::from_secs(5), 4).await;
+    assert!(started_at.elapsed() < Duration::from_secs(5));
+    assert_eq!(
+        summary,
+        LongPollSummary {
+            received: 3,
+            completed: 3,
+            abandoned: 0,
+            confirmed_offset: Some(4),
+        }
+    );
+}
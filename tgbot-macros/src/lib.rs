@@ -0,0 +1,351 @@
+//! Procedural macros for [tgbot](https://docs.rs/tgbot).
+//!
+//! Use the `macros` feature of `tgbot` instead of depending on this crate directly.
+
+use std::collections::HashSet;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Attribute,
+    Data,
+    DeriveInput,
+    Error,
+    Expr,
+    ExprLit,
+    Field,
+    Fields,
+    GenericArgument,
+    Lit,
+    LitStr,
+    Meta,
+    PathArguments,
+    Result,
+    Type,
+    parse_macro_input,
+};
+
+const MAX_NAME_LEN: usize = 32;
+const MIN_DESCRIPTION_LEN: usize = 3;
+const MAX_DESCRIPTION_LEN: usize = 256;
+
+/// Derives `tgbot::types::BotCommands` for an enum.
+///
+/// Each variant is a command:
+///
+/// * the name of the command is the name of the variant in `snake_case`;
+///   use `#[command(rename = "name")]` to override it;
+/// * the description of the command is taken from the doc comment of the variant;
+/// * fields of the variant are arguments of the command, parsed in order using `FromStr`;
+///   fields of the `Option<T>` type are optional and must follow required ones.
+///
+/// # Example
+///
+/// ```ignore
+/// use tgbot::types::BotCommands;
+///
+/// #[derive(BotCommands)]
+/// enum Cmd {
+///     /// Start the bot
+///     Start { payload: Option<String> },
+///     /// Ban a user
+///     Ban { user: i64, minutes: u32 },
+///     /// Show help
+///     #[command(rename = "help")]
+///     ShowHelp,
+/// }
+/// ```
+#[proc_macro_derive(BotCommands, attributes(command))]
+pub fn derive_bot_commands(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_bot_commands(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_bot_commands(input: DeriveInput) -> Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(&input, "BotCommands can be derived only for enums"));
+    };
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut parse_arms = Vec::new();
+    let mut bot_commands = Vec::new();
+    let mut names = HashSet::new();
+    for variant in &data.variants {
+        let variant_ident = &variant.ident;
+        let name = get_name(&variant.attrs)?.unwrap_or_else(|| to_snake_case(&variant_ident.to_string()));
+        validate_name(&name, variant_ident)?;
+        if !names.insert(name.clone()) {
+            return Err(Error::new_spanned(
+                variant_ident,
+                format!("the name of the command `{name}` is already used"),
+            ));
+        }
+        let description = get_description(&variant.attrs);
+        if !(MIN_DESCRIPTION_LEN..=MAX_DESCRIPTION_LEN).contains(&description.chars().count()) {
+            return Err(Error::new_spanned(
+                variant_ident,
+                format!(
+                    "a doc comment with {MIN_DESCRIPTION_LEN}-{MAX_DESCRIPTION_LEN} characters \
+                     is required for a description of the command"
+                ),
+            ));
+        }
+
+        let fields: Vec<_> = variant.fields.iter().collect();
+        validate_optional_fields(&fields)?;
+        let expected = fields.len();
+        let values = fields.iter().enumerate().map(|(idx, field)| {
+            let arg_name = match &field.ident {
+                Some(field_ident) => field_ident.to_string(),
+                None => idx.to_string(),
+            };
+            let (ty, is_optional) = match get_option_type(&field.ty) {
+                Some(ty) => (ty, true),
+                None => (&field.ty, false),
+            };
+            let value = quote! {
+                value.parse::<#ty>().map_err(|err| ::tgbot::types::ParseCommandError::InvalidArgument {
+                    name: #arg_name,
+                    value: value.clone(),
+                    error: err.to_string(),
+                })?
+            };
+            let value = if is_optional {
+                quote! {
+                    match args.get(#idx) {
+                        Some(value) => Some(#value),
+                        None => None,
+                    }
+                }
+            } else {
+                quote! {
+                    match args.get(#idx) {
+                        Some(value) => #value,
+                        None => return Err(::tgbot::types::ParseCommandError::MissingArgument { name: #arg_name }),
+                    }
+                }
+            };
+            match &field.ident {
+                Some(field_ident) => quote!(#field_ident: #value),
+                None => value,
+            }
+        });
+        let constructor = match &variant.fields {
+            Fields::Named(_) => quote!(Self::#variant_ident { #(#values),* }),
+            Fields::Unnamed(_) => quote!(Self::#variant_ident(#(#values),*)),
+            Fields::Unit => quote!(Self::#variant_ident),
+        };
+        let command = format!("/{name}");
+        parse_arms.push(quote! {
+            #command => {
+                if args.len() > #expected {
+                    return Err(::tgbot::types::ParseCommandError::TooManyArguments {
+                        expected: #expected,
+                        actual: args.len(),
+                    });
+                }
+                Ok(#constructor)
+            }
+        });
+        bot_commands.push(quote! {
+            ::tgbot::types::BotCommand::new(#name, #description).expect("The command is validated by the macro")
+        });
+    }
+    Ok(quote! {
+        impl #impl_generics ::tgbot::types::BotCommands for #ident #ty_generics #where_clause {
+            fn from_command(
+                command: &::tgbot::types::Command,
+            ) -> ::std::result::Result<Self, ::tgbot::types::ParseCommandError> {
+                let args = command.get_args();
+                match command.get_name() {
+                    #(#parse_arms)*
+                    name => Err(::tgbot::types::ParseCommandError::UnknownCommand(name.to_string())),
+                }
+            }
+
+            fn bot_commands() -> ::std::vec::Vec<::tgbot::types::BotCommand> {
+                ::std::vec![#(#bot_commands),*]
+            }
+        }
+    })
+}
+
+fn get_name(attrs: &[Attribute]) -> Result<Option<String>> {
+    let mut name = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("command")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                let value: LitStr = meta.value()?.parse()?;
+                name = Some(value.value());
+                Ok(())
+            } else {
+                Err(meta.error("unsupported attribute, expected `rename`"))
+            }
+        })?;
+    }
+    Ok(name)
+}
+
+fn validate_name(name: &str, ident: &syn::Ident) -> Result<()> {
+    if name.is_empty()
+        || name.chars().count() > MAX_NAME_LEN
+        || !name
+            .bytes()
+            .all(|x| x.is_ascii_lowercase() || x.is_ascii_digit() || x == b'_')
+    {
+        return Err(Error::new_spanned(
+            ident,
+            format!(
+                "the name of the command `{name}` must contain 1-{MAX_NAME_LEN} lowercase English letters, \
+                 digits and underscores"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_optional_fields(fields: &[&Field]) -> Result<()> {
+    let mut has_optional = false;
+    for field in fields {
+        if get_option_type(&field.ty).is_some() {
+            has_optional = true;
+        } else if has_optional {
+            return Err(Error::new_spanned(
+                field,
+                "a required argument can not follow an optional one; move `Option` fields to the end",
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn get_description(attrs: &[Attribute]) -> String {
+    attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(value), ..
+                }) => Some(value.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn get_option_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(ty) = ty else {
+        return None;
+    };
+    let segment = ty.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(GenericArgument::Type(ty)) if args.args.len() == 1 => Some(ty),
+        _ => None,
+    }
+}
+
+fn to_snake_case(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for (idx, c) in value.chars().enumerate() {
+        if c.is_uppercase() {
+            if idx > 0 {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn expand_error(input: DeriveInput) -> String {
+        expand_bot_commands(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn expand() {
+        assert!(
+            expand_bot_commands(parse_quote! {
+                enum Cmd {
+                    /// Описание команды
+                    Start { user: i64, payload: Option<String> },
+                }
+            })
+            .is_ok()
+        );
+        let description = "д".repeat(MAX_DESCRIPTION_LEN);
+        assert!(
+            expand_bot_commands(parse_quote! {
+                enum Cmd {
+                    #[doc = #description]
+                    Start,
+                }
+            })
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn expand_errors() {
+        let err = expand_error(parse_quote! {
+            enum Cmd {
+                /// Start the bot
+                Start { payload: Option<String>, user: i64 },
+            }
+        });
+        assert!(err.starts_with("a required argument can not follow an optional one"));
+
+        let err = expand_error(parse_quote! {
+            enum Cmd {
+                /// Start the bot
+                #[command(rename = "команда")]
+                Start,
+            }
+        });
+        assert!(err.starts_with("the name of the command `команда`"));
+
+        let err = expand_error(parse_quote! {
+            enum Cmd {
+                /// Start the bot
+                StartBot,
+                /// Start the bot
+                #[command(rename = "start_bot")]
+                Start,
+            }
+        });
+        assert_eq!(err, "the name of the command `start_bot` is already used");
+
+        let description = "д".repeat(MAX_DESCRIPTION_LEN + 1);
+        let err = expand_error(parse_quote! {
+            enum Cmd {
+                #[doc = #description]
+                Start,
+            }
+        });
+        assert!(err.starts_with("a doc comment with"));
+
+        let err = expand_error(parse_quote! {
+            struct Cmd;
+        });
+        assert_eq!(err, "BotCommands can be derived only for enums");
+    }
+}